@@ -1,8 +1,11 @@
+use std::f64::consts::PI;
+
 use crate::Time;
 
 /// Radian
 ///
 /// Notation: `r`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Radian(f64);
 
 impl Radian {
@@ -15,13 +18,62 @@ impl Radian {
 /// Angle
 ///
 /// Notation: `θ`
+///
+/// Units: `rad`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Angle(Radian);
 
+impl Angle {
+    pub fn new(value: f64) -> Self {
+        Angle(Radian::new(value))
+    }
+
+    pub fn val(&self) -> &f64 { self.0.val() }
+    pub fn radian(&self) -> Radian { self.0 }
+
+    /// The same direction expressed within `[0, 2π)`
+    pub fn wrapped(&self) -> Angle {
+        let wrapped = self.val().rem_euclid(2.0 * PI);
+
+        // Tiny negative angles round up to exactly 2π
+        Angle::new(if wrapped < 2.0 * PI { wrapped } else { 0.0 })
+    }
+
+    /// The same direction expressed within `(-π, π]`
+    pub fn wrapped_signed(&self) -> Angle {
+        let wrapped = self.wrapped();
+
+        if *wrapped.val() > PI { wrapped - Angle::new(2.0 * PI) } else { wrapped }
+    }
+
+    /// Interpolates towards `other` along the shorter of the two arcs between them
+    ///
+    /// `t = 0` gives this angle, `t = 1` the direction of `other`.
+    pub fn lerp(&self, other: Angle, t: f64) -> Angle {
+        (*self + (other - *self).wrapped_signed() * t).wrapped()
+    }
+
+    // Trigonometry
+    pub fn sin(&self) -> f64 { self.val().sin() }
+    pub fn cos(&self) -> f64 { self.val().cos() }
+    pub fn tan(&self) -> f64 { self.val().tan() }
+    pub fn sin_cos(&self) -> (f64, f64) { self.val().sin_cos() }
+}
+
+impl From<Radian> for Angle {
+    fn from(radian: Radian) -> Self {
+        Angle(radian)
+    }
+}
+
 /// Angular velocity
 ///
 /// Notation: `ω`
 ///
 /// Definition: `ω = dθ / dt`
+///
+/// Units: `rad / s`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct AngularVelocity(f64);
 
 impl AngularVelocity {
@@ -30,12 +82,38 @@ impl AngularVelocity {
     }
 
     pub fn from_delta(delta_angle: Radian, delta_time: Time) -> Self {
-        AngularVelocity(
-            delta_angle.val() / delta_time.val()
-        )
+        delta_angle / delta_time
     }
 
     pub fn val(&self) -> &f64 { &self.0 }
 }
 
-pub struct AngularAcceleration(f64);
\ No newline at end of file
+/// Angular acceleration
+///
+/// Units: `rad / s²`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct AngularAcceleration(f64);
+
+impl AngularAcceleration {
+    pub fn new(value: f64) -> Self {
+        AngularAcceleration(value)
+    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
+}
+
+#[test]
+fn angles_wrap_and_interpolate_along_the_shorter_arc() {
+    let almost_full = Angle::new(2.0 * PI - 0.1);
+
+    assert!((almost_full.wrapped_signed().val() + 0.1).abs() < 1.0e-12);
+    assert!((Angle::new(-0.1).wrapped().val() - almost_full.val()).abs() < 1.0e-12);
+    assert_eq!(Angle::new(-1.0e-20).wrapped(), Angle::new(0.0));
+    assert_eq!(Angle::new(PI).wrapped_signed(), Angle::new(PI));
+    assert_eq!(Angle::new(-PI).wrapped_signed(), Angle::new(PI));
+
+    // Crossing zero rather than going the long way round
+    let halfway = almost_full.lerp(Angle::new(0.3), 0.5);
+    assert!((halfway.val() - 0.1).abs() < 1.0e-12);
+    assert!(Angle::new(0.3).lerp(almost_full, 0.75).wrapped_signed().val().abs() < 1.0e-12);
+}
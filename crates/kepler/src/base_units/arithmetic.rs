@@ -0,0 +1,124 @@
+//! Operators between the base units, so that only dimensionally consistent expressions compile
+//!
+//! ```compile_fail
+//! use kepler::{Length, Time};
+//!
+//! let nonsense = Length::new(1.0) + Time::new(1.0);
+//! ```
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::{Acceleration, Angle, AngularAcceleration, AngularVelocity, Area, Density, Length, Mass, Radian, Time, Velocity, Volume};
+
+
+/// Sums, differences and scaling of a quantity with itself and with plain numbers
+macro_rules! quantity {
+    ($quantity:ident) => {
+        impl Add for $quantity {
+            type Output = $quantity;
+            fn add(self, other: $quantity) -> $quantity { $quantity::new(self.val() + other.val()) }
+        }
+
+        impl Sub for $quantity {
+            type Output = $quantity;
+            fn sub(self, other: $quantity) -> $quantity { $quantity::new(self.val() - other.val()) }
+        }
+
+        impl Neg for $quantity {
+            type Output = $quantity;
+            fn neg(self) -> $quantity { $quantity::new(-self.val()) }
+        }
+
+        impl Mul<f64> for $quantity {
+            type Output = $quantity;
+            fn mul(self, factor: f64) -> $quantity { $quantity::new(self.val() * factor) }
+        }
+
+        impl Mul<$quantity> for f64 {
+            type Output = $quantity;
+            fn mul(self, quantity: $quantity) -> $quantity { $quantity::new(self * quantity.val()) }
+        }
+
+        impl Div<f64> for $quantity {
+            type Output = $quantity;
+            fn div(self, divisor: f64) -> $quantity { $quantity::new(self.val() / divisor) }
+        }
+
+        /// The ratio of two quantities of the same kind is a plain number
+        impl Div for $quantity {
+            type Output = f64;
+            fn div(self, other: $quantity) -> f64 { self.val() / other.val() }
+        }
+    };
+}
+
+/// `product = left * right`, along with the commuted product and both quotients
+macro_rules! product {
+    ($product:ident = $left:ident * $right:ident) => {
+        impl Mul<$right> for $left {
+            type Output = $product;
+            fn mul(self, other: $right) -> $product { $product::new(self.val() * other.val()) }
+        }
+
+        impl Mul<$left> for $right {
+            type Output = $product;
+            fn mul(self, other: $left) -> $product { $product::new(self.val() * other.val()) }
+        }
+
+        impl Div<$left> for $product {
+            type Output = $right;
+            fn div(self, other: $left) -> $right { $right::new(self.val() / other.val()) }
+        }
+
+        impl Div<$right> for $product {
+            type Output = $left;
+            fn div(self, other: $right) -> $left { $left::new(self.val() / other.val()) }
+        }
+    };
+}
+
+quantity!(Mass);
+quantity!(Length);
+quantity!(Area);
+quantity!(Volume);
+quantity!(Density);
+quantity!(Time);
+quantity!(Velocity);
+quantity!(Acceleration);
+quantity!(Radian);
+quantity!(Angle);
+quantity!(AngularVelocity);
+quantity!(AngularAcceleration);
+
+product!(Volume = Area * Length);
+product!(Mass = Density * Volume);
+product!(Length = Velocity * Time);
+product!(Velocity = Acceleration * Time);
+product!(Radian = AngularVelocity * Time);
+product!(AngularVelocity = AngularAcceleration * Time);
+
+/// `A = l²`, written out since both factors are the same type
+impl Mul for Length {
+    type Output = Area;
+    fn mul(self, other: Length) -> Area { Area::new(self.val() * other.val()) }
+}
+
+impl Div<Length> for Area {
+    type Output = Length;
+    fn div(self, other: Length) -> Length { Length::new(self.val() / other.val()) }
+}
+
+#[test]
+fn units_combine_into_derived_units() {
+    let distance = Length::new(300.0);
+    let duration = Time::new(20.0);
+
+    assert_eq!(distance / duration, Velocity::new(15.0));
+    assert_eq!(Velocity::new(15.0) * duration, distance);
+    assert_eq!(distance / Velocity::new(15.0), duration);
+    assert_eq!(Mass::new(50.0) / Volume::new(10.0), Density::new(5.0));
+    assert_eq!(Radian::new(3.0) / Time::new(2.0), AngularVelocity::new(1.5));
+    assert_eq!(distance * Length::new(2.0) * Length::new(0.5), Volume::new(300.0));
+    assert_eq!(distance - Length::new(100.0), 2.0 * Length::new(100.0));
+    assert_eq!(distance / Length::new(100.0), 3.0);
+}
@@ -3,6 +3,7 @@
 /// Notation: `m`
 ///
 /// Units: `kg`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Mass(f64);
 
 impl Mass {
@@ -18,39 +19,51 @@ impl Mass {
 /// Notation: `A`
 ///
 /// Units: `m²`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Area(f64);
 
+impl Area {
+    pub fn new(value: f64) -> Self {
+        Area(value)
+    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
+}
+
 /// Volume
 ///
 /// Notation: `V`
 ///
 /// Units: `m³`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Volume(f64);
 
 impl Volume {
-    fn new(volume: f64) -> Self {
+    pub fn new(volume: f64) -> Self {
         Volume(volume)
     }
-    fn value(&self) -> f64 {
-        self.0
-    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
 }
 
 /// Density
 ///
 /// Notation: `ρ`
 ///
-/// Definition: `ρ = m * V`
+/// Definition: `ρ = m / V`
 ///
 /// Units: `kg / m³`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Density(f64);
 
 impl Density {
-    fn new(density: f64) -> Self {
+    pub fn new(density: f64) -> Self {
         Density(density)
     }
 
-    fn from_mass_volume(mass: Mass, volume: Volume) -> Self {
-        Density(mass.0 / volume.0)
+    pub fn from_mass_volume(mass: Mass, volume: Volume) -> Self {
+        mass / volume
     }
+
+    pub fn val(&self) -> &f64 { &self.0 }
 }
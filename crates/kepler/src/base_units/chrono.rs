@@ -3,6 +3,7 @@
 /// Notation: `T`
 ///
 /// Units: `second`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Time(f64);
 
 impl Time {
@@ -11,4 +12,4 @@ impl Time {
     }
     
     pub fn val(&self) -> &f64 { &self.0 }
-}
\ No newline at end of file
+}
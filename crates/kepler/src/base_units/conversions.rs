@@ -0,0 +1,88 @@
+//! Conversions between the SI units the base units are stored in and common astronomical units
+
+use std::f64::consts::PI;
+
+use crate::{Angle, Length, Mass, Radian, Time, Velocity};
+
+
+/// Mean distance from the Earth to the Sun, in meters
+pub const ASTRONOMICAL_UNIT: f64 = 1.495_978_707e11;
+
+/// Seconds in a day
+pub const DAY: f64 = 86_400.0;
+
+/// Seconds in a Julian year of 365.25 days
+pub const YEAR: f64 = 365.25 * DAY;
+
+/// Nominal mass of the Sun, in kilograms
+pub const SOLAR_MASS: f64 = 1.988_47e30;
+
+/// Nominal mass of the Earth, in kilograms
+pub const EARTH_MASS: f64 = 5.972_2e24;
+
+impl Length {
+    pub fn from_meters(meters: f64) -> Self { Length::new(meters) }
+    pub fn from_kilometers(kilometers: f64) -> Self { Length::new(kilometers * 1.0e3) }
+    pub fn from_astronomical_units(astronomical_units: f64) -> Self { Length::new(astronomical_units * ASTRONOMICAL_UNIT) }
+
+    pub fn as_meters(&self) -> f64 { *self.val() }
+    pub fn as_kilometers(&self) -> f64 { self.val() / 1.0e3 }
+    pub fn as_astronomical_units(&self) -> f64 { self.val() / ASTRONOMICAL_UNIT }
+}
+
+impl Time {
+    pub fn from_seconds(seconds: f64) -> Self { Time::new(seconds) }
+    pub fn from_days(days: f64) -> Self { Time::new(days * DAY) }
+    pub fn from_years(years: f64) -> Self { Time::new(years * YEAR) }
+
+    pub fn as_seconds(&self) -> f64 { *self.val() }
+    pub fn as_days(&self) -> f64 { self.val() / DAY }
+    pub fn as_years(&self) -> f64 { self.val() / YEAR }
+}
+
+impl Mass {
+    pub fn from_kilograms(kilograms: f64) -> Self { Mass::new(kilograms) }
+    pub fn from_earth_masses(earth_masses: f64) -> Self { Mass::new(earth_masses * EARTH_MASS) }
+    pub fn from_solar_masses(solar_masses: f64) -> Self { Mass::new(solar_masses * SOLAR_MASS) }
+
+    pub fn as_kilograms(&self) -> f64 { *self.val() }
+    pub fn as_earth_masses(&self) -> f64 { self.val() / EARTH_MASS }
+    pub fn as_solar_masses(&self) -> f64 { self.val() / SOLAR_MASS }
+}
+
+impl Radian {
+    pub fn from_degrees(degrees: f64) -> Self { Radian::new(degrees * PI / 180.0) }
+
+    pub fn as_degrees(&self) -> f64 { self.val() * 180.0 / PI }
+}
+
+impl Angle {
+    pub fn from_degrees(degrees: f64) -> Self { Angle::from(Radian::from_degrees(degrees)) }
+
+    pub fn as_degrees(&self) -> f64 { self.radian().as_degrees() }
+}
+
+impl Velocity {
+    pub fn from_meters_per_second(meters_per_second: f64) -> Self { Velocity::new(meters_per_second) }
+    pub fn from_kilometers_per_second(kilometers_per_second: f64) -> Self { Velocity::new(kilometers_per_second * 1.0e3) }
+
+    pub fn as_meters_per_second(&self) -> f64 { *self.val() }
+    pub fn as_kilometers_per_second(&self) -> f64 { self.val() / 1.0e3 }
+}
+
+#[test]
+fn earth_orbit_in_astronomical_units() {
+    use crate::GravitationalParameter;
+
+    let semimajor_axis = Length::from_astronomical_units(1.0);
+    let mu = GravitationalParameter::new(Mass::from_solar_masses(1.0));
+    let period = Time::new(2.0 * PI * (semimajor_axis.val().powf(3.0) / mu.val()).sqrt());
+
+    assert!((period.as_years() - 1.0).abs() < 1.0e-3, "{}", period.as_years());
+    assert!((Velocity::from_kilometers_per_second(29.78).as_meters_per_second() - 29_780.0).abs() < 1.0e-9);
+    assert!(((semimajor_axis / period).as_kilometers_per_second() * 2.0 * PI - 29.78).abs() < 0.01);
+    assert!((Radian::from_degrees(180.0).val() - PI).abs() < 1.0e-15);
+    assert!((Mass::from_earth_masses(332_946.0).as_solar_masses() - 1.0).abs() < 1.0e-3);
+    assert_eq!(Length::from_kilometers(1.0).as_meters(), 1.0e3);
+    assert_eq!(Time::from_days(365.25).as_years(), 1.0);
+}
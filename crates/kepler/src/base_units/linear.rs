@@ -3,18 +3,49 @@ use bevy_math::DVec3;
 /// Velocity
 ///
 /// Notation: `v`
-pub struct Veclocity(f64);
+///
+/// Units: `m / s`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Velocity(f64);
 pub struct VelocityVec3(DVec3);
 
+impl Velocity {
+    pub fn new(value: f64) -> Self {
+        Velocity(value)
+    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
+}
+
 /// Acceleration
+///
+/// Units: `m / s²`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Acceleration(f64);
 pub struct AccelerationVec3(DVec3);
 
+impl Acceleration {
+    pub fn new(value: f64) -> Self {
+        Acceleration(value)
+    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
+}
+
 /// Distance
 ///
 /// Notation: `l`
 ///
 /// Units: `m`
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Length(f64);
 pub struct LengthVec3(DVec3);
-pub struct Position(DVec3);
\ No newline at end of file
+pub struct Position(DVec3);
+
+impl Length {
+    pub fn new(value: f64) -> Self {
+        Length(value)
+    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
+}
@@ -2,8 +2,11 @@ mod base;
 mod angular;
 mod linear;
 mod chrono;
+mod arithmetic;
+mod conversions;
 
 pub use base::*;
 pub use angular::*;
 pub use linear::*;
 pub use chrono::*;
+pub use conversions::*;
@@ -1,41 +1,104 @@
-use std::f32::consts::PI;
+use std::f64::consts::PI;
+
+use bevy_math::DVec3;
+
+use crate::{AngularVelocity, Atmosphere, Density, EllipticalOrbit, GravitationalParameter, Length, Mass, SecularRates, Volume, sphere_of_influence};
+
 /// A spherical body that can undergo orbit
 
 #[derive(Debug, Copy, Clone)]
 pub struct OrbitalBody {
-    pub mass: f32,
-    pub radius: f32,
-    pub density: f32,
-    pub volume: f32,
-    pub spin_velocity: f32,
+    pub mass: Mass,
+    pub radius: Length,
+    pub density: Density,
+    pub volume: Volume,
+
+    /// Rate of rotation around the body's Y axis
+    pub spin_velocity: AngularVelocity,
+
+    /// Second zonal harmonic of the gravity field, measuring the equatorial bulge
+    ///
+    /// Zero for a perfect sphere. Notation: `J2`
+    pub j2: f64,
+
+    /// Gas envelope slowing down orbiters that dip into it
+    pub atmosphere: Option<Atmosphere>,
     // axial_tilt: Angle, // TODO: Quaternion?
 }
 
 impl OrbitalBody {
-    pub fn new(mass: f32, radius: f32, density: f32, volume: f32, spin_velocity: f32) -> Self {
+    pub fn new(mass: Mass, radius: Length, density: Density, volume: Volume, spin_velocity: AngularVelocity) -> Self {
         OrbitalBody {
             mass,
             radius,
             density,
             volume,
             spin_velocity,
+            j2: 0.0,
+            atmosphere: None,
         }
     }
 
-    pub fn from_sphere(radius: f32, density: f32, spin_velocity: f32) -> Self {
+    pub fn from_sphere(radius: Length, density: Density, spin_velocity: AngularVelocity) -> Self {
         let volume = sphere_volume(radius);
-        let mass = volume * density;
+        let mass = density * volume;
 
         OrbitalBody {
             radius,
             density,
             spin_velocity,
             volume,
-            mass 
+            mass,
+            j2: 0.0,
+            atmosphere: None,
+        }
+    }
+
+    /// The Sun, from its mean radius, mean density and equatorial rotation
+    pub fn sun() -> Self {
+        Self::from_sphere(Length::from_kilometers(696_340.0), Density::new(1_408.0), AngularVelocity::new(2.9e-6))
+    }
+
+    pub fn gravitational_parameter(&self) -> GravitationalParameter {
+        GravitationalParameter::new(self.mass)
+    }
+
+    /// Precession of an orbit around this body caused by its oblateness
+    pub fn oblateness_rates(&self, orbit: &EllipticalOrbit) -> SecularRates {
+        SecularRates::oblateness(
+            self.j2,
+            *self.radius.val(),
+            *orbit.semimajor_axis().val(),
+            orbit.eccentricity(),
+            *orbit.inclination().val(),
+            *orbit.mean_angular_motion().val(),
+        )
+    }
+
+    /// Height above the surface of a point at the given position relative to the center
+    pub fn altitude(&self, position: DVec3) -> Length {
+        Length::new(position.length()) - self.radius
+    }
+
+    /// Drag on an orbiter with the given ballistic coefficient, zero outside the atmosphere
+    ///
+    /// The atmosphere turns with the body around its Y axis.
+    pub fn drag_acceleration(&self, position: DVec3, velocity: DVec3, ballistic_coefficient: f64) -> DVec3 {
+        match self.atmosphere {
+            Some(atmosphere) => {
+                let relative_velocity = velocity - DVec3::Y.cross(position) * *self.spin_velocity.val();
+                atmosphere.drag_acceleration(self.altitude(position), relative_velocity, ballistic_coefficient)
+            }
+            None => DVec3::ZERO,
         }
     }
+
+    /// Radius of the sphere of influence of this body, orbiting `parent` with the given semi-major axis
+    pub fn sphere_of_influence(&self, parent: &OrbitalBody, semimajor_axis: Length) -> Length {
+        sphere_of_influence(semimajor_axis, self.mass, parent.mass)
+    }
 }
 
-pub fn sphere_volume(radius: f32) -> f32 {
-    4.0 / 3.0 * PI * radius.powf(3.0)
+pub fn sphere_volume(radius: Length) -> Volume {
+    4.0 / 3.0 * PI * radius * radius * radius
 }
\ No newline at end of file
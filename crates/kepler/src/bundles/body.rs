@@ -5,7 +5,7 @@ use bevy_pbr::PbrBundle;
 use bevy_render::mesh::{Mesh, shape};
 use bevy_transform::components::Transform;
 
-use crate::OrbitalBody;
+use crate::{AngularVelocity, Density, Length, OrbitalBody};
 
 #[derive(Bundle)]
 pub struct OrbitalBodyBundle {
@@ -20,13 +20,18 @@ pub struct OrbitalBodyBundle {
 }
 
 impl OrbitalBodyBundle {
-    pub fn new(radius: f32, density: f32, spin_velocity: f32, transform: Transform, mesh_handle: &mut ResMut<Assets<Mesh>>) -> Self {
+    pub fn new(radius: Length, density: Density, spin_velocity: AngularVelocity, transform: Transform, mesh_handle: &mut ResMut<Assets<Mesh>>) -> Self {
+        Self::from_orbital_body(OrbitalBody::from_sphere(radius, density, spin_velocity), *radius.val() as f32, transform, mesh_handle)
+    }
+
+    /// Bundle of an existing body, drawn as a sphere of `display_radius` in world units
+    pub fn from_orbital_body(body: OrbitalBody, display_radius: f32, transform: Transform, mesh_handle: &mut ResMut<Assets<Mesh>>) -> Self {
         OrbitalBodyBundle {
-            body: OrbitalBody::from_sphere(radius, density, spin_velocity),
+            body,
             geometry: PbrBundle {
                 mesh: mesh_handle.add(Mesh::from(
                     shape::Icosphere { 
-                        radius,
+                        radius: display_radius,
                         subdivisions: 1 
                     }
                 )),
@@ -2,10 +2,11 @@ use bevy_ecs::prelude::Bundle;
 use bevy_math::{Quat, Vec3};
 use bevy_transform::components::{GlobalTransform, Transform};
 
-use crate::{Ellipse, OrbitalDirectionsLocal, OrbitalPlane, TransformBundle, transform_from_axis};
+use crate::{Ellipse, EllipticalOrbit, Length, OrbitalDirectionsLocal, OrbitalPlane, TransformBundle, transform_from_axis};
 
 #[derive(Bundle)]
 pub struct EllipticalOrbitBundle {
+    orbit: EllipticalOrbit,
     ellipse: Ellipse,
     plane: OrbitalPlane,
     #[bundle]
@@ -13,11 +14,9 @@ pub struct EllipticalOrbitBundle {
 }
 
 impl EllipticalOrbitBundle {
-    pub fn new(
-        semi_major: f32, eccentricity: f32, 
-        ascending_angle: f32, inclination_angle: f32, periapsis_angle: f32,
-    ) -> Self {
-        let plane = OrbitalPlane::new(ascending_angle, inclination_angle, periapsis_angle);
+    /// Frame following `orbit`, with its ellipse drawn at `world_length` per world unit
+    pub fn new(orbit: EllipticalOrbit, world_length: Length) -> Self {
+        let plane = OrbitalPlane::new(orbit.longitude_of_ascending_node(), orbit.inclination(), orbit.argument_of_periapsis());
 
         let transform = Transform {
             translation: Vec3::ZERO,
@@ -26,9 +25,10 @@ impl EllipticalOrbitBundle {
         };
 
         EllipticalOrbitBundle {
-            ellipse: Ellipse::from_major(semi_major, eccentricity),
+            orbit,
+            ellipse: Ellipse::from_major(orbit.semimajor_axis() / world_length, orbit.eccentricity()),
             plane,
             transform: TransformBundle::from_transform(transform),
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,239 @@
+//! Adaptive step integration with the embedded Dormand–Prince 5(4) Runge-Kutta pair
+//!
+//! The difference between the fifth and fourth order solutions estimates the error of each step,
+//! which sets the size of the next one: short near close approaches, long far from them.
+//!
+//! https://en.wikipedia.org/wiki/Dormand%E2%80%93Prince_method
+
+use bevy_math::DVec3;
+
+use crate::{GravitationalParameter, OrbitalPositionVector, OrbitalVelocityVector, Time};
+
+
+/// Time of each stage as a fraction of the step
+const C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const A: [[f64; 6]; 7] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+    [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+    [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+/// Weights of the fifth order solution, the same as the last stage so it is evaluated first-same-as-last
+const B: [f64; 7] = [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0, 0.0];
+/// Weights of the embedded fourth order solution
+const B_STAR: [f64; 7] = [5179.0 / 57600.0, 0.0, 7571.0 / 16695.0, 393.0 / 640.0, -92097.0 / 339200.0, 187.0 / 2100.0, 1.0 / 40.0];
+
+/// Settings of the adaptive integrator
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DormandPrince {
+    /// Allowed error of each step, relative to the size of the state
+    pub relative_tolerance: f64,
+
+    /// Allowed error of each step, for components of the state close to zero
+    pub absolute_tolerance: f64,
+
+    /// Longest step the integrator may take, however small the error
+    pub max_step: f64,
+}
+
+/// Work done by the adaptive integrator
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StepStatistics {
+    pub accepted_steps: u32,
+
+    /// Steps whose error exceeded the tolerance and were retried shorter
+    pub rejected_steps: u32,
+
+    /// Number of times the derivative was evaluated
+    pub evaluations: u32,
+    pub smallest_step: f64,
+    pub largest_step: f64,
+}
+
+impl Default for StepStatistics {
+    fn default() -> Self {
+        StepStatistics {
+            accepted_steps: 0,
+            rejected_steps: 0,
+            evaluations: 0,
+            smallest_step: f64::INFINITY,
+            largest_step: 0.0,
+        }
+    }
+}
+
+impl StepStatistics {
+    /// Combined statistics of two successive integrations
+    pub fn merge(&self, other: &StepStatistics) -> StepStatistics {
+        StepStatistics {
+            accepted_steps: self.accepted_steps + other.accepted_steps,
+            rejected_steps: self.rejected_steps + other.rejected_steps,
+            evaluations: self.evaluations + other.evaluations,
+            smallest_step: self.smallest_step.min(other.smallest_step),
+            largest_step: self.largest_step.max(other.largest_step),
+        }
+    }
+}
+
+impl DormandPrince {
+    pub fn new(relative_tolerance: f64, absolute_tolerance: f64) -> Self {
+        DormandPrince {
+            relative_tolerance,
+            absolute_tolerance,
+            max_step: f64::INFINITY,
+        }
+    }
+
+    /// Integrates a second order system `r'' = a(t, r)` over `duration`
+    ///
+    /// The acceleration receives the time elapsed since the start of the integration.
+    /// Returns the final positions and velocities and the statistics of the steps taken,
+    /// or `None` if the error estimate stops being finite, as when a body falls into a singularity.
+    pub fn integrate<F>(&self, positions: &[DVec3], velocities: &[DVec3], duration: f64, acceleration: F) -> Option<(Vec<DVec3>, Vec<DVec3>, StepStatistics)>
+    where
+        F: Fn(f64, &[DVec3]) -> Vec<DVec3>,
+    {
+        let count = positions.len();
+        let mut statistics = StepStatistics::default();
+        let mut state: Vec<DVec3> = positions.iter().chain(velocities).copied().collect();
+
+        if duration == 0.0 || count == 0 {
+            return Some((positions.to_vec(), velocities.to_vec(), statistics));
+        }
+
+        // State is positions followed by velocities, its derivative velocities followed by accelerations
+        let derivative = |time: f64, state: &[DVec3]| -> Vec<DVec3> {
+            let mut result = state[count..].to_vec();
+            result.extend(acceleration(time, &state[..count]));
+            result
+        };
+
+        let direction = duration.signum();
+        let mut elapsed = 0.0;
+        let mut step = (0.01 * duration.abs()).min(self.max_step);
+        let mut first_stage = derivative(0.0, &state);
+        statistics.evaluations += 1;
+
+        while elapsed < duration.abs() {
+            step = step.min(duration.abs() - elapsed);
+            let h = direction * step;
+
+            let mut stages: Vec<Vec<DVec3>> = vec![first_stage.clone()];
+            for (i, coefficients) in A.iter().enumerate().skip(1) {
+                let stage_state: Vec<DVec3> = (0..state.len())
+                    .map(|n| state[n] + h * weighted_sum(&coefficients[..i], &stages, n))
+                    .collect();
+                stages.push(derivative(direction * elapsed + C[i] * h, &stage_state));
+                statistics.evaluations += 1;
+            }
+
+            let next: Vec<DVec3> = (0..state.len())
+                .map(|n| state[n] + h * weighted_sum(&B, &stages, n))
+                .collect();
+            let embedded: Vec<DVec3> = (0..state.len())
+                .map(|n| state[n] + h * weighted_sum(&B_STAR, &stages, n))
+                .collect();
+
+            let error = self.error_norm(&state, &next, &embedded);
+            if !error.is_finite() {
+                return None;
+            }
+
+            let smallest_allowed = 1.0e-12 * duration.abs();
+
+            if error <= 1.0 || step <= smallest_allowed {
+                elapsed += step;
+                state = next;
+                first_stage = stages.pop().unwrap_or_default();
+
+                statistics.accepted_steps += 1;
+                statistics.smallest_step = statistics.smallest_step.min(step);
+                statistics.largest_step = statistics.largest_step.max(step);
+            } else {
+                statistics.rejected_steps += 1;
+            }
+
+            let factor = if error > 0.0 { 0.9 * error.powf(-0.2) } else { 5.0 };
+            step = (step * factor.clamp(0.2, 5.0)).clamp(smallest_allowed, self.max_step);
+        }
+
+        let velocities = state.split_off(count);
+        Some((state, velocities, statistics))
+    }
+
+    /// Advances a body around a parent with gravitational parameter `mu` by `time_of_flight`
+    ///
+    /// `None` if the integration fails, see `integrate`.
+    pub fn propagate_two_body(
+        &self,
+        position: OrbitalPositionVector,
+        velocity: OrbitalVelocityVector,
+        mu: GravitationalParameter,
+        time_of_flight: Time,
+    ) -> Option<(OrbitalPositionVector, OrbitalVelocityVector, StepStatistics)> {
+        let mu = *mu.val();
+        let acceleration = |_: f64, positions: &[DVec3]| -> Vec<DVec3> {
+            positions.iter().map(|r| -mu * *r / r.length().powf(3.0)).collect()
+        };
+
+        let (positions, velocities, statistics) = self.integrate(&[*position.val()], &[*velocity.val()], *time_of_flight.val(), acceleration)?;
+
+        Some((OrbitalPositionVector::new(positions[0]), OrbitalVelocityVector::new(velocities[0]), statistics))
+    }
+
+    /// Root mean square of the error estimate, scaled by the tolerance of each component
+    fn error_norm(&self, state: &[DVec3], next: &[DVec3], embedded: &[DVec3]) -> f64 {
+        let mut sum = 0.0;
+
+        for n in 0..state.len() {
+            let scale = DVec3::splat(self.absolute_tolerance) + self.relative_tolerance * state[n].abs().max(next[n].abs());
+            let scaled = (next[n] - embedded[n]) / scale;
+            sum += scaled.length_squared();
+        }
+
+        (sum / (3 * state.len()) as f64).sqrt()
+    }
+}
+
+/// Combination of the stage derivatives of one component of the state
+fn weighted_sum(weights: &[f64], stages: &[Vec<DVec3>], component: usize) -> DVec3 {
+    weights.iter().zip(stages).fold(DVec3::ZERO, |sum, (weight, stage)| sum + *weight * stage[component])
+}
+
+#[test]
+fn dormand_prince_follows_an_eccentric_orbit() {
+    use crate::{Angle, EllipticalOrbit, Length};
+
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let orbit = EllipticalOrbit::new(0.8, Length::new(3.0e7), Angle::new(0.0), Angle::new(0.3), Angle::new(1.0), Angle::new(0.6), mu);
+    let (position, velocity) = orbit.state_vectors_at(Time::new(0.0));
+    let time_of_flight = orbit.period() * 0.3;
+    let (expected, _) = orbit.state_vectors_at(time_of_flight);
+
+    let loose = DormandPrince::new(1.0e-8, 1.0e-6);
+    let tight = DormandPrince::new(1.0e-11, 1.0e-9);
+    let (loose_position, _, loose_statistics) = loose.propagate_two_body(position, velocity, mu, time_of_flight).unwrap();
+    let (tight_position, _, tight_statistics) = tight.propagate_two_body(position, velocity, mu, time_of_flight).unwrap();
+
+    let loose_error = (*loose_position.val() - *expected.val()).length();
+    let tight_error = (*tight_position.val() - *expected.val()).length();
+    assert!(loose_error < 1.0e2, "{}", loose_error);
+    assert!(tight_error < 1.0, "{}", tight_error);
+    assert!(tight_statistics.accepted_steps > loose_statistics.accepted_steps);
+
+    // Steps shrink close to the periapsis, where the motion is fastest
+    assert!(tight_statistics.largest_step > 10.0 * tight_statistics.smallest_step);
+}
+
+#[test]
+fn dormand_prince_gives_up_at_a_singularity() {
+    // A body at rest on top of its parent has no finite acceleration
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let integrator = DormandPrince::new(1.0e-9, 1.0e-9);
+    let result = integrator.propagate_two_body(OrbitalPositionVector::new(DVec3::ZERO), OrbitalVelocityVector::new(DVec3::ZERO), mu, Time::new(100.0));
+
+    assert!(result.is_none());
+}
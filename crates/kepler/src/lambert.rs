@@ -0,0 +1,271 @@
+//! Lambert's problem: the conic connecting two positions in a given time of flight
+//!
+//! Solved with the method by Izzo, "Revisiting Lambert's problem" (2015),
+//! which covers single and multiple revolution transfers with a Householder iteration.
+//!
+//! https://en.wikipedia.org/wiki/Lambert%27s_problem
+
+use std::f64::consts::PI;
+
+use crate::{EllipticalOrbit, GravitationalParameter, OrbitalPositionVector, OrbitalVelocityVector, Time};
+
+/// Sense of motion of the transfer, relative to the parent's pole (+Y)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransferDirection {
+    Prograde,
+    Retrograde,
+}
+
+/// A transfer orbit found by the Lambert solver
+#[derive(Debug, Copy, Clone)]
+pub struct LambertSolution {
+    /// The transfer orbit, with its true anomaly at the departure position
+    pub orbit: EllipticalOrbit,
+    /// Velocity needed at the departure position
+    pub departure_velocity: OrbitalVelocityVector,
+    /// Velocity on arrival at the arrival position
+    pub arrival_velocity: OrbitalVelocityVector,
+}
+
+/// Solves for the transfer orbit from `departure` to `arrival` in `time_of_flight`
+///
+/// With `revolutions > 0` the transfer completes that many full turns first. Each multiple
+/// revolution transfer has two solutions; `low_path` picks the one with the lower energy
+/// period branch (the "left" branch in Izzo's terms), otherwise the "right" one.
+///
+/// Returns `None` when no bound transfer exists for the inputs: when the positions are
+/// collinear with the focus, the time of flight is too short for the number of revolutions,
+/// or the only transfer is hyperbolic.
+pub fn solve(
+    departure: OrbitalPositionVector,
+    arrival: OrbitalPositionVector,
+    time_of_flight: Time,
+    mu: GravitationalParameter,
+    direction: TransferDirection,
+    revolutions: u32,
+    low_path: bool,
+) -> Option<LambertSolution> {
+    let (r1, r2, k) = (*departure.val(), *arrival.val(), *mu.val());
+    let chord = (r2 - r1).length();
+    let (r1_norm, r2_norm) = (r1.length(), r2.length());
+    let semi_perimeter = (r1_norm + r2_norm + chord) / 2.0;
+
+    let (i_r1, i_r2) = (r1 / r1_norm, r2 / r2_norm);
+    let i_h = i_r1.cross(i_r2);
+    if i_h.length() < 1.0e-12 {
+        return None;
+    }
+    let i_h = i_h.normalize();
+
+    let mut lambda = (1.0 - (chord / semi_perimeter).min(1.0)).sqrt();
+    let (mut i_t1, mut i_t2) = if i_h.y < 0.0 {
+        lambda = -lambda;
+        (i_r1.cross(i_h), i_r2.cross(i_h))
+    } else {
+        (i_h.cross(i_r1), i_h.cross(i_r2))
+    };
+    if direction == TransferDirection::Retrograde {
+        lambda = -lambda;
+        i_t1 = -i_t1;
+        i_t2 = -i_t2;
+    }
+
+    let time = (2.0 * k / semi_perimeter.powf(3.0)).sqrt() * time_of_flight.val();
+    let x = find_x(lambda, time, revolutions, low_path)?;
+    let y = compute_y(x, lambda);
+
+    let gamma = (k * semi_perimeter / 2.0).sqrt();
+    let rho = (r1_norm - r2_norm) / chord;
+    let sigma = (1.0 - rho.powf(2.0)).sqrt();
+
+    let radial_1 = gamma * ((lambda * y - x) - rho * (lambda * y + x)) / r1_norm;
+    let radial_2 = -gamma * ((lambda * y - x) + rho * (lambda * y + x)) / r2_norm;
+    let tangential_1 = gamma * sigma * (y + lambda * x) / r1_norm;
+    let tangential_2 = gamma * sigma * (y + lambda * x) / r2_norm;
+
+    let departure_velocity = OrbitalVelocityVector::new(radial_1 * i_r1 + tangential_1 * i_t1);
+    let arrival_velocity = OrbitalVelocityVector::new(radial_2 * i_r2 + tangential_2 * i_t2);
+
+    let orbit = EllipticalOrbit::from_state_vectors(departure, departure_velocity, mu)?;
+
+    Some(LambertSolution {
+        orbit,
+        departure_velocity,
+        arrival_velocity,
+    })
+}
+
+fn find_x(lambda: f64, time: f64, revolutions: u32, low_path: bool) -> Option<f64> {
+    let revolutions = revolutions as f64;
+    let mut max_revolutions = (time / PI).floor();
+    let time_00 = lambda.acos() + lambda * (1.0 - lambda.powf(2.0)).sqrt();
+
+    // The fastest transfer for the highest revolution count may still be too slow,
+    // and without a minimum to compare against that count is out of reach as well
+    if max_revolutions > 0.0 && time < time_00 + max_revolutions * PI
+        && !minimum_time(lambda, max_revolutions).is_some_and(|minimum| time >= minimum)
+    {
+        max_revolutions -= 1.0;
+    }
+    if revolutions > max_revolutions {
+        return None;
+    }
+
+    let guess = initial_guess(time, lambda, revolutions, low_path);
+
+    householder(guess, time, lambda, revolutions)
+}
+
+fn compute_y(x: f64, lambda: f64) -> f64 {
+    (1.0 - lambda.powf(2.0) * (1.0 - x.powf(2.0))).sqrt()
+}
+
+fn compute_psi(x: f64, y: f64, lambda: f64) -> f64 {
+    if (-1.0..1.0).contains(&x) {
+        (x * y + lambda * (1.0 - x.powf(2.0))).acos()
+    } else if x > 1.0 {
+        ((y - x * lambda) * (x.powf(2.0) - 1.0).sqrt()).asinh()
+    } else {
+        0.0
+    }
+}
+
+/// Non-dimensional time of flight at `x`, using a series close to the parabolic case
+fn time_of_flight_at(x: f64, y: f64, lambda: f64, revolutions: f64) -> f64 {
+    if revolutions == 0.0 && x > 0.6f64.sqrt() && x < 1.4f64.sqrt() {
+        let eta = y - lambda * x;
+        let s1 = (1.0 - lambda - x * eta) / 2.0;
+        let q = 4.0 / 3.0 * hypergeometric_2f1(s1);
+
+        (eta.powf(3.0) * q + 4.0 * lambda * eta) / 2.0
+    } else {
+        let psi = compute_psi(x, y, lambda);
+
+        ((psi + revolutions * PI) / (1.0 - x.powf(2.0)).abs().sqrt() - x + lambda * y) / (1.0 - x.powf(2.0))
+    }
+}
+
+fn derivatives(x: f64, y: f64, time: f64, lambda: f64) -> (f64, f64, f64) {
+    let denominator = 1.0 - x.powf(2.0);
+    let first = (3.0 * time * x - 2.0 + 2.0 * lambda.powf(3.0) * x / y) / denominator;
+    let second = (3.0 * time + 5.0 * x * first + 2.0 * (1.0 - lambda.powf(2.0)) * lambda.powf(3.0) / y.powf(3.0)) / denominator;
+    let third = (7.0 * x * second + 8.0 * first - 6.0 * (1.0 - lambda.powf(2.0)) * lambda.powf(5.0) * x / y.powf(5.0)) / denominator;
+
+    (first, second, third)
+}
+
+/// Gauss hypergeometric function `2F1(3, 1, 5/2, x)`
+fn hypergeometric_2f1(x: f64) -> f64 {
+    if x >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let mut result = 1.0;
+    let mut term = 1.0;
+    let mut n = 0.0;
+    loop {
+        term *= (3.0 + n) * (1.0 + n) / (2.5 + n) * x / (n + 1.0);
+        let previous = result;
+        result += term;
+        if result == previous {
+            return result;
+        }
+        n += 1.0;
+    }
+}
+
+/// Minimum time of flight for a multiple revolution transfer, found with Halley's method
+fn minimum_time(lambda: f64, revolutions: f64) -> Option<f64> {
+    let mut x = 0.1;
+
+    for _ in 0..64 {
+        let y = compute_y(x, lambda);
+        let time = time_of_flight_at(x, y, lambda, revolutions);
+        let (first, second, third) = derivatives(x, y, time, lambda);
+        let next = x - 2.0 * first * second / (2.0 * second.powf(2.0) - first * third);
+
+        if (next - x).abs() < 1.0e-13 {
+            return Some(time_of_flight_at(next, compute_y(next, lambda), lambda, revolutions));
+        }
+        x = next;
+    }
+
+    None
+}
+
+fn initial_guess(time: f64, lambda: f64, revolutions: f64, low_path: bool) -> f64 {
+    if revolutions == 0.0 {
+        let time_0 = lambda.acos() + lambda * (1.0 - lambda.powf(2.0)).sqrt();
+        let time_1 = 2.0 * (1.0 - lambda.powf(3.0)) / 3.0;
+
+        if time >= time_0 {
+            (time_0 / time).powf(2.0 / 3.0) - 1.0
+        } else if time < time_1 {
+            2.5 * time_1 / time * (time_1 - time) / (1.0 - lambda.powf(5.0)) + 1.0
+        } else {
+            (time_0 / time).powf((time_1 / time_0).log2()) - 1.0
+        }
+    } else {
+        let left = ((revolutions * PI + PI) / (8.0 * time)).powf(2.0 / 3.0);
+        let right = ((8.0 * time) / (revolutions * PI)).powf(2.0 / 3.0);
+        let x_left = (left - 1.0) / (left + 1.0);
+        let x_right = (right - 1.0) / (right + 1.0);
+
+        if low_path { x_left.min(x_right) } else { x_left.max(x_right) }
+    }
+}
+
+fn householder(guess: f64, target_time: f64, lambda: f64, revolutions: f64) -> Option<f64> {
+    let mut x = guess;
+
+    for _ in 0..64 {
+        let y = compute_y(x, lambda);
+        let time = time_of_flight_at(x, y, lambda, revolutions);
+        let residual = time - target_time;
+        let (first, second, third) = derivatives(x, y, time, lambda);
+
+        let next = x - residual * (first.powf(2.0) - residual * second / 2.0)
+            / (first * (first.powf(2.0) - residual * second) + third * residual.powf(2.0) / 6.0);
+
+        if (next - x).abs() < 1.0e-13 {
+            return Some(next);
+        }
+        if !next.is_finite() {
+            return None;
+        }
+        x = next;
+    }
+
+    None
+}
+
+#[test]
+fn lambert_recovers_known_orbit() {
+    use crate::{Angle, Length};
+
+    let mu = GravitationalParameter::from_value(1.327e20);
+    let orbit = EllipticalOrbit::new(0.2, Length::new(1.8e11), Angle::new(0.0), Angle::new(0.3), Angle::new(0.9), Angle::new(0.1), mu);
+
+    let (departure, departure_velocity) = orbit.state_vectors_at(Time::new(1.0e6));
+    let (arrival, arrival_velocity) = orbit.state_vectors_at(Time::new(9.0e6));
+
+    let solution = solve(departure, arrival, Time::new(8.0e6), mu, TransferDirection::Prograde, 0, true).unwrap();
+    assert!((*solution.departure_velocity.val() - *departure_velocity.val()).length() < 1.0e-3);
+    assert!((*solution.arrival_velocity.val() - *arrival_velocity.val()).length() < 1.0e-3);
+    assert!(((solution.orbit.semimajor_axis() - orbit.semimajor_axis()) / orbit.semimajor_axis()).abs() < 1.0e-9);
+
+    // One extra lap: the orbit we started from is the higher energy branch,
+    // the lower one a tighter orbit with a shorter period
+    let time_of_flight = Time::new(8.0e6) + orbit.period();
+    let high = solve(departure, arrival, time_of_flight, mu, TransferDirection::Prograde, 1, false).unwrap();
+    assert!((*high.departure_velocity.val() - *departure_velocity.val()).length() < 1.0e-3);
+    assert!(((high.orbit.semimajor_axis() - orbit.semimajor_axis()) / orbit.semimajor_axis()).abs() < 1.0e-9);
+
+    let low = solve(departure, arrival, time_of_flight, mu, TransferDirection::Prograde, 1, true).unwrap();
+    assert!(((low.orbit.semimajor_axis() - Length::new(1.458e11)) / Length::new(1.458e11)).abs() < 1.0e-3, "{:?}", low.orbit.semimajor_axis());
+
+    // Going the long way around passes the parent in the opposite sense
+    let retrograde = solve(departure, arrival, Time::new(8.0e6), mu, TransferDirection::Retrograde, 0, true).unwrap();
+    let normal = departure.val().cross(*retrograde.departure_velocity.val());
+    assert!(normal.dot(departure.val().cross(*departure_velocity.val())) < 0.0);
+}
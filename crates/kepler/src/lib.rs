@@ -5,6 +5,11 @@ mod shapes;
 mod plane;
 mod bundles;
 mod utils;
+mod transfer;
+mod nbody;
+mod dormand_prince;
+mod perturbations;
+pub mod lambert;
 
 pub use base_units::*;
 pub use orbits::*;
@@ -13,11 +18,14 @@ pub use shapes::*;
 pub use plane::*;
 pub use bundles::*;
 pub use utils::*;
+pub use transfer::*;
+pub use nbody::*;
+pub use dormand_prince::*;
+pub use perturbations::*;
 
-use std::f32::consts::PI as PI32;
 use std::f64::consts::PI as PI64;
+use bevy_math::DVec3;
 use physical_constants::NEWTONIAN_CONSTANT_OF_GRAVITATION;
-use rand::*;
 
 /// In a two-body problem with inverse-square-law force, every orbit is a Kepler orbit. The eccentricity of this Kepler orbit is a non-negative number that defines its shape.
 ///
@@ -38,16 +46,23 @@ struct Eccentricity(f64);
 ///
 /// Definition:
 /// `μ = GM`
-struct GravitationalParameter(f64);
+#[derive(Debug, Copy, Clone)]
+pub struct GravitationalParameter(f64);
 
 impl GravitationalParameter {
     pub fn new(mass: Mass) -> GravitationalParameter {
         GravitationalParameter(mass.val() * NEWTONIAN_CONSTANT_OF_GRAVITATION)
     }
+
+    pub fn from_value(value: f64) -> GravitationalParameter {
+        GravitationalParameter(value)
+    }
+
+    pub fn val(&self) -> &f64 { &self.0 }
 }
 
 #[derive(Debug)]
-struct MeanAnomaly(f32);
+struct MeanAnomaly(f64);
 
 
 struct EscapeVelocity(f64); // Should simply be Velocity?
@@ -61,8 +76,38 @@ impl EscapeVelocity {
 
 /// Orbital State Vectors
 /// https://en.wikipedia.org/wiki/Orbital_state_vectors
-struct OrbitalPositionVector();
-struct OrbitalVelocityVector();
+///
+/// Position of the orbiting body relative to the focus, in the parent's frame of reference
+///
+/// Notation: `r`
+///
+/// Units: `m`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitalPositionVector(DVec3);
+
+impl OrbitalPositionVector {
+    pub fn new(value: DVec3) -> Self {
+        OrbitalPositionVector(value)
+    }
+
+    pub fn val(&self) -> &DVec3 { &self.0 }
+}
+
+/// Velocity of the orbiting body relative to the focus, in the parent's frame of reference
+///
+/// Notation: `v`
+///
+/// Units: `m / s`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitalVelocityVector(DVec3);
+
+impl OrbitalVelocityVector {
+    pub fn new(value: DVec3) -> Self {
+        OrbitalVelocityVector(value)
+    }
+
+    pub fn val(&self) -> &DVec3 { &self.0 }
+}
 
 /// Circular orbital velocity at radius r
 /// v = sqrt(G*M/r^2)
@@ -81,56 +126,236 @@ struct OrbitalVelocityVector();
 struct Dummy;
 
 
-// TODO: Make this an PID controller
-pub fn eccentric_anomaly_solver(mean_anomaly: f32, eccentricity: f32) -> f32 {
-    let mut rng = rand::thread_rng();
-    let equality_threshold = 0.000001;
-    let iterations = 100;
-    // let period = OrbitalPeriod::new(Time::new(2.5));
-    // // let mean_anomaly = MeanAngularMotion::from_period(period);
-    // let mean_anomaly = 2. * PI / 365.0 * 24.0 * 60.0 * 60.0;
-    // let eccentricity = 0.3;
-
-    // M = E - e sin E
-    // initial value of E
-    
-    let lower_bound = mean_anomaly - 10.0;
-    let upper_bound = mean_anomaly + 10.0;
-    let mut guess = rng.gen_range(lower_bound..upper_bound); // rng.gen_range(0.0..10.0);
-    let mut exit_iteration = 0;
-    // TOOD: Replace with a PID controller
-    for n in 0..iterations {
-        // run the calc
-        let result = calc_eccentric(guess, eccentricity);
-        let difference = mean_anomaly - result;
-
-        // println!("Guess {:?} | Target: {:?} | Result: {:?} | Difference: {:?} | Upper: {:?} | Lower: {:?} | ", guess, mean_anomaly, result, difference, upper_bound, lower_bound);
-        if difference.abs() <= equality_threshold { break; }
-        
-        guess += difference * 0.995;
-        exit_iteration = n;
-    }
-    // println!("E is {:?} | {:?}", guess, exit_iteration);
-
-    guess
-}
-
-pub fn calc_eccentric(eccentric_anomaly: f32, eccentricity: f32) -> f32 {
+/// Outcome of an iterative anomaly solve
+#[derive(Debug, Copy, Clone)]
+pub struct AnomalySolution {
+    /// The anomaly the solver settled on
+    pub anomaly: f64,
+    /// Number of iterations that were run
+    pub iterations: u32,
+    /// Remaining error of Kepler's equation at `anomaly`
+    pub residual: f64,
+    /// Whether the residual ended up within the solver tolerance
+    pub converged: bool,
+}
+
+/// Solves Kepler's equation `M = E - e sin E` for the eccentric anomaly `E`
+///
+/// Uses Halley's method from the Danby starter `E0 = M + 0.85 e sign(sin M)`,
+/// which converges for all `0 <= e < 1` in a handful of iterations.
+/// The solve is deterministic: the same input always yields the same anomaly.
+pub fn eccentric_anomaly_solver(mean_anomaly: f64, eccentricity: f64) -> AnomalySolution {
+    let tolerance = 1.0e-12;
+    let max_iterations = 32;
+
+    // Solve in (-π, π] and add the whole revolutions back afterwards
+    let revolutions = (mean_anomaly / (2.0 * PI64)).round();
+    let reduced = mean_anomaly - revolutions * 2.0 * PI64;
+
+    let mut anomaly = reduced + 0.85 * eccentricity * reduced.sin().signum();
+    let mut residual = calc_eccentric(anomaly, eccentricity) - reduced;
+    let mut iterations = 0;
+
+    while residual.abs() > tolerance && iterations < max_iterations {
+        let (sin, cos) = anomaly.sin_cos();
+        let first = 1.0 - eccentricity * cos;
+        let second = eccentricity * sin;
+
+        anomaly -= 2.0 * residual * first / (2.0 * first * first - residual * second);
+        residual = calc_eccentric(anomaly, eccentricity) - reduced;
+        iterations += 1;
+    }
+
+    AnomalySolution {
+        anomaly: anomaly + revolutions * 2.0 * PI64,
+        iterations,
+        residual,
+        converged: residual.abs() <= tolerance,
+    }
+}
+
+pub fn calc_eccentric(eccentric_anomaly: f64, eccentricity: f64) -> f64 {
     eccentric_anomaly - eccentricity * eccentric_anomaly.sin()
 }
 
-pub fn calc_true_anomaly(eccentricity: f32, eccentric_anomaly: f32) -> f32 {
+pub fn calc_true_anomaly(eccentricity: f64, eccentric_anomaly: f64) -> f64 {
     2.0 * (((1.0 + eccentricity) / (1.0 - eccentricity)).sqrt() * (eccentric_anomaly / 2.0).tan()).atan()
 }
 
-pub fn radius_at_true_anomaly(eccentricity: f32, true_anomaly: f32, semi_major_axis: f32) -> f32 {
+pub fn calc_eccentric_anomaly(eccentricity: f64, true_anomaly: f64) -> f64 {
+    2.0 * (((1.0 - eccentricity) / (1.0 + eccentricity)).sqrt() * (true_anomaly / 2.0).tan()).atan()
+}
+
+/// Solves the hyperbolic Kepler equation `M = e sinh H - H` for the hyperbolic anomaly `H`
+///
+/// Uses Halley's method from the starter `H0 = sign(M) ln(2|M| / e + 1.8)`,
+/// which converges for all `e > 1`, including large mean anomalies far out on the asymptotes.
+pub fn hyperbolic_anomaly_solver(mean_anomaly: f64, eccentricity: f64) -> AnomalySolution {
+    let tolerance = 1.0e-12 * mean_anomaly.abs().max(1.0);
+    let max_iterations = 64;
+
+    let mut anomaly = mean_anomaly.signum() * (2.0 * mean_anomaly.abs() / eccentricity + 1.8).ln();
+    let mut residual = calc_hyperbolic(anomaly, eccentricity) - mean_anomaly;
+    let mut iterations = 0;
+
+    while residual.abs() > tolerance && iterations < max_iterations {
+        let first = eccentricity * anomaly.cosh() - 1.0;
+        let second = eccentricity * anomaly.sinh();
+
+        anomaly -= 2.0 * residual * first / (2.0 * first * first - residual * second);
+        residual = calc_hyperbolic(anomaly, eccentricity) - mean_anomaly;
+        iterations += 1;
+    }
+
+    AnomalySolution {
+        anomaly,
+        iterations,
+        residual,
+        converged: residual.abs() <= tolerance,
+    }
+}
+
+pub fn calc_hyperbolic(hyperbolic_anomaly: f64, eccentricity: f64) -> f64 {
+    eccentricity * hyperbolic_anomaly.sinh() - hyperbolic_anomaly
+}
+
+pub fn calc_true_anomaly_hyperbolic(eccentricity: f64, hyperbolic_anomaly: f64) -> f64 {
+    2.0 * (((eccentricity + 1.0) / (eccentricity - 1.0)).sqrt() * (hyperbolic_anomaly / 2.0).tanh()).atan()
+}
+
+pub fn calc_hyperbolic_anomaly(eccentricity: f64, true_anomaly: f64) -> f64 {
+    2.0 * (((eccentricity - 1.0) / (eccentricity + 1.0)).sqrt() * (true_anomaly / 2.0).tan()).atanh()
+}
+
+/// Solves Barker's equation `M = D + D³ / 3` for the parabolic anomaly `D = tan(θ / 2)`
+///
+/// The cubic has a single real root, found in closed form so no iteration is needed.
+pub fn barker_solver(mean_anomaly: f64) -> f64 {
+    let half = 1.5 * mean_anomaly;
+    let root = (half.abs() + (half * half + 1.0).sqrt()).cbrt();
+
+    half.signum() * (root - 1.0 / root)
+}
+
+pub fn calc_barker(parabolic_anomaly: f64) -> f64 {
+    parabolic_anomaly + parabolic_anomaly.powf(3.0) / 3.0
+}
+
+/// Stumpff function `C(z)`, used by the universal formulation of Kepler's equation
+///
+/// `C(z) = (1 - cos √z) / z`, continued through `z = 0` and into negative `z` with cosh
+pub fn stumpff_c(z: f64) -> f64 {
+    if z.abs() < 1.0e-3 {
+        1.0 / 2.0 - z / 24.0 + z.powf(2.0) / 720.0 - z.powf(3.0) / 40320.0
+    } else if z > 0.0 {
+        (1.0 - z.sqrt().cos()) / z
+    } else {
+        ((-z).sqrt().cosh() - 1.0) / -z
+    }
+}
+
+/// Stumpff function `S(z)`, used by the universal formulation of Kepler's equation
+///
+/// `S(z) = (√z - sin √z) / √z³`, continued through `z = 0` and into negative `z` with sinh
+pub fn stumpff_s(z: f64) -> f64 {
+    if z.abs() < 1.0e-3 {
+        1.0 / 6.0 - z / 120.0 + z.powf(2.0) / 5040.0 - z.powf(3.0) / 362880.0
+    } else if z > 0.0 {
+        let root = z.sqrt();
+        (root - root.sin()) / root.powf(3.0)
+    } else {
+        let root = (-z).sqrt();
+        (root.sinh() - root) / root.powf(3.0)
+    }
+}
+
+/// Solves the universal Kepler equation for the universal anomaly `χ` after `time_of_flight`
+///
+/// `√μ Δt = r₀ v_r₀ / √μ χ² C(αχ²) + (1 - α r₀) χ³ S(αχ²) + r₀ χ`
+///
+/// `alpha` is the reciprocal semi-major axis `α = 1 / a`, which is zero for parabolic trajectories.
+/// Uses Newton's method, whose derivative is the radius at `χ`, from the starters by Vallado
+/// for the elliptic, parabolic and hyperbolic regimes.
+pub fn universal_anomaly_solver(radius: f64, radial_velocity: f64, alpha: f64, mu: f64, time_of_flight: f64) -> AnomalySolution {
+    let sqrt_mu = mu.sqrt();
+    let tolerance = 1.0e-12 * (sqrt_mu * time_of_flight).abs().max(1.0);
+    let max_iterations = 64;
+
+    let mut anomaly = if alpha > 1.0e-12 {
+        sqrt_mu * time_of_flight * alpha
+    } else if alpha < -1.0e-12 {
+        let semimajor_axis = 1.0 / alpha;
+        let direction = time_of_flight.signum();
+        let argument = -2.0 * mu * alpha * time_of_flight
+            / (radius * radial_velocity + direction * (-mu * semimajor_axis).sqrt() * (1.0 - radius * alpha));
+
+        direction * (-semimajor_axis).sqrt() * argument.ln()
+    } else {
+        let semi_latus_rectum = 2.0 * radius - radius.powf(2.0) * radial_velocity.powf(2.0) / mu;
+        let s = 0.5 * (1.0 / (3.0 * (mu / semi_latus_rectum.powf(3.0)).sqrt() * time_of_flight)).atan();
+        let w = s.tan().cbrt().atan();
+
+        semi_latus_rectum.sqrt() * 2.0 / (2.0 * w).tan()
+    };
+    if !anomaly.is_finite() {
+        anomaly = sqrt_mu * time_of_flight / radius;
+    }
+
+    let universal_time = |anomaly: f64| {
+        let z = alpha * anomaly.powf(2.0);
+
+        radius * radial_velocity / sqrt_mu * anomaly.powf(2.0) * stumpff_c(z)
+            + (1.0 - alpha * radius) * anomaly.powf(3.0) * stumpff_s(z)
+            + radius * anomaly
+    };
+
+    let mut residual = universal_time(anomaly) - sqrt_mu * time_of_flight;
+    let mut iterations = 0;
+
+    while residual.abs() > tolerance && iterations < max_iterations {
+        let z = alpha * anomaly.powf(2.0);
+        let derivative = radius * radial_velocity / sqrt_mu * anomaly * (1.0 - z * stumpff_s(z))
+            + (1.0 - alpha * radius) * anomaly.powf(2.0) * stumpff_c(z)
+            + radius;
+
+        anomaly -= residual / derivative;
+        residual = universal_time(anomaly) - sqrt_mu * time_of_flight;
+        iterations += 1;
+    }
+
+    AnomalySolution {
+        anomaly,
+        iterations,
+        residual,
+        converged: residual.abs() <= tolerance,
+    }
+}
+
+pub fn radius_at_true_anomaly(eccentricity: f64, true_anomaly: f64, semi_major_axis: f64) -> f64 {
     // Check if there should be a 1.0 + eccentricity below the divider
     (semi_major_axis * (1.0 - eccentricity.powf(2.0))) / (1.0 + eccentricity * true_anomaly.cos())
 }
 
-/// Orbital Period
-pub fn orbital_period(semimajor_axis: f32, mass: f32) -> f32 {
-    2. * PI32 * (semimajor_axis.powf(3.0) / mass * NEWTONIAN_CONSTANT_OF_GRAVITATION as f32).sqrt()
+/// Orbital Period, from Kepler's third law
+///
+/// `T = 2π √(a³ / μ)`
+pub fn orbital_period(semimajor_axis: Length, mu: GravitationalParameter) -> Time {
+    Time::new(2. * PI64 * (semimajor_axis.val().powf(3.0) / mu.val()).sqrt())
+}
+
+/// Semi-major axis of the orbit with the given period, from Kepler's third law
+///
+/// `a = ∛(μ (T / 2π)²)`
+pub fn semimajor_axis_from_period(period: Time, mu: GravitationalParameter) -> Length {
+    Length::new((mu.val() * (period.val() / (2. * PI64)).powf(2.0)).cbrt())
+}
+
+/// Laplace sphere of influence, the radius around an orbiting body within which
+/// its own gravity dominates the motion of a third body over that of its parent
+///
+/// `r = a (m / M)^(2/5)`
+pub fn sphere_of_influence(semimajor_axis: Length, mass: Mass, parent_mass: Mass) -> Length {
+    semimajor_axis * (mass / parent_mass).powf(0.4)
 }
 
 
@@ -146,4 +371,48 @@ fn test() {
 
 
     println!("Result: {:?}", result);   
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eccentric_anomaly_solver_is_deterministic() {
+        let first = eccentric_anomaly_solver(1.3, 0.6);
+        let second = eccentric_anomaly_solver(1.3, 0.6);
+
+        assert_eq!(first.anomaly, second.anomaly);
+        assert_eq!(first.iterations, second.iterations);
+    }
+
+    #[test]
+    fn eccentric_anomaly_solver_converges_at_high_eccentricity() {
+        for &eccentricity in &[0.0, 0.3, 0.9, 0.99, 0.999] {
+            for n in -20..=20 {
+                let mean_anomaly = n as f64 * 0.5;
+                let solution = eccentric_anomaly_solver(mean_anomaly, eccentricity);
+
+                assert!(solution.converged, "e = {}, M = {}: {:?}", eccentricity, mean_anomaly, solution);
+                assert!(solution.iterations <= 8, "e = {}, M = {}: {:?}", eccentricity, mean_anomaly, solution);
+            }
+        }
+    }
+
+    #[test]
+    fn orbital_period_of_earth() {
+        let period = orbital_period(Length::from_astronomical_units(1.0), GravitationalParameter::new(Mass::from_solar_masses(1.0)));
+
+        assert!((period.as_days() - 365.25).abs() < 0.1, "{}", period.as_days());
+
+        let semimajor_axis = semimajor_axis_from_period(period, GravitationalParameter::new(Mass::from_solar_masses(1.0)));
+        assert!((semimajor_axis.as_astronomical_units() - 1.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn sphere_of_influence_of_earth() {
+        let radius = sphere_of_influence(Length::new(1.496e11), Mass::new(5.972e24), Mass::new(1.989e30));
+
+        assert!((radius.val() - 9.25e8).abs() < 0.01e8);
+    }
+}
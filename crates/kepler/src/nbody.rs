@@ -0,0 +1,228 @@
+//! Numerical propagation of bodies under their mutual gravitational attraction
+//!
+//! An alternative to the analytic two-body propagators, for studying how bodies perturb each other.
+//!
+//! https://en.wikipedia.org/wiki/N-body_problem
+
+use bevy_math::DVec3;
+
+use crate::{DormandPrince, GravitationalParameter, OrbitalBody, OrbitalPositionVector, OrbitalVelocityVector, StepStatistics};
+
+
+/// Numerical scheme used to advance an N-body simulation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Integrator {
+    /// Classic fourth order Runge-Kutta, accurate per step but slowly drifting in energy
+    RungeKutta4,
+
+    /// Second order leapfrog in kick-drift-kick form,
+    /// symplectic so the energy error stays bounded over long runs
+    Leapfrog,
+
+    /// Adaptive fifth order Runge-Kutta, taking as many substeps per step as the tolerance needs
+    DormandPrince(DormandPrince),
+}
+
+/// A body reduced to its position, velocity and gravitational parameter
+#[derive(Debug, Copy, Clone)]
+pub struct PointMass {
+    pub position: DVec3,
+    pub velocity: DVec3,
+    pub gravitational_parameter: GravitationalParameter,
+}
+
+impl PointMass {
+    pub fn new(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, gravitational_parameter: GravitationalParameter) -> Self {
+        PointMass {
+            position: *position.val(),
+            velocity: *velocity.val(),
+            gravitational_parameter,
+        }
+    }
+
+    pub fn from_body(body: &OrbitalBody, position: OrbitalPositionVector, velocity: OrbitalVelocityVector) -> Self {
+        Self::new(position, velocity, body.gravitational_parameter())
+    }
+}
+
+/// A set of point masses all attracting each other, sharing one inertial frame of reference
+#[derive(Debug, Clone)]
+pub struct NBodySimulation {
+    bodies: Vec<PointMass>,
+    time: f64,
+    integrator: Integrator,
+
+    /// Accumulated over every step taken with the adaptive integrator
+    statistics: StepStatistics,
+}
+
+impl NBodySimulation {
+    // Constructors
+    pub fn new(bodies: Vec<PointMass>, integrator: Integrator) -> Self {
+        NBodySimulation {
+            bodies,
+            time: 0.0,
+            integrator,
+            statistics: StepStatistics::default(),
+        }
+    }
+
+    // Getters
+    pub fn bodies(&self) -> &[PointMass] { &self.bodies }
+    pub fn time(&self) -> f64 { self.time }
+    pub fn integrator(&self) -> Integrator { self.integrator }
+    pub fn statistics(&self) -> StepStatistics { self.statistics }
+
+    // Setters
+    pub fn set_integrator(&mut self, value: Integrator) { self.integrator = value; }
+
+    /// Advances every body by one step of `dt`
+    ///
+    /// Returns `false`, leaving the bodies where they were, if the adaptive integrator fails.
+    pub fn step(&mut self, dt: f64) -> bool {
+        let parameters: Vec<f64> = self.bodies.iter().map(|body| *body.gravitational_parameter.val()).collect();
+        let positions: Vec<DVec3> = self.bodies.iter().map(|body| body.position).collect();
+        let velocities: Vec<DVec3> = self.bodies.iter().map(|body| body.velocity).collect();
+
+        let (positions, velocities) = match self.integrator {
+            Integrator::RungeKutta4 => runge_kutta_4_step(&positions, &velocities, &parameters, dt),
+            Integrator::Leapfrog => leapfrog_step(&positions, &velocities, &parameters, dt),
+            Integrator::DormandPrince(settings) => {
+                let integrated = settings.integrate(&positions, &velocities, dt, |_, positions| gravitational_accelerations(positions, &parameters));
+                let (positions, velocities, statistics) = match integrated {
+                    Some(integrated) => integrated,
+                    None => return false,
+                };
+                self.statistics = self.statistics.merge(&statistics);
+
+                (positions, velocities)
+            }
+        };
+
+        for ((body, position), velocity) in self.bodies.iter_mut().zip(positions).zip(velocities) {
+            body.position = position;
+            body.velocity = velocity;
+        }
+        self.time += dt;
+
+        true
+    }
+
+    /// Advances every body by `duration`, in equal steps no longer than `max_step`
+    ///
+    /// Stops at the first step that fails, returning `false`.
+    pub fn propagate(&mut self, duration: f64, max_step: f64) -> bool {
+        let steps = (duration.abs() / max_step).ceil().max(1.0) as u32;
+        let dt = duration / steps as f64;
+
+        (0..steps).all(|_| self.step(dt))
+    }
+
+    /// Kinetic plus potential energy of the whole system, multiplied by the gravitational constant
+    ///
+    /// Constant for the exact motion, so its drift measures the integration error.
+    pub fn total_energy(&self) -> f64 {
+        let mut energy = 0.0;
+
+        for (i, body) in self.bodies.iter().enumerate() {
+            let mu = body.gravitational_parameter.val();
+            energy += 0.5 * mu * body.velocity.length_squared();
+
+            for other in &self.bodies[i + 1..] {
+                energy -= mu * other.gravitational_parameter.val() / (other.position - body.position).length();
+            }
+        }
+
+        energy
+    }
+}
+
+/// Acceleration of each body due to the attraction of all the others
+///
+/// `aᵢ = Σ μⱼ (rⱼ - rᵢ) / |rⱼ - rᵢ|³`
+pub fn gravitational_accelerations(positions: &[DVec3], gravitational_parameters: &[f64]) -> Vec<DVec3> {
+    let mut accelerations = vec![DVec3::ZERO; positions.len()];
+
+    for i in 0..positions.len() {
+        for j in i + 1..positions.len() {
+            let separation = positions[j] - positions[i];
+            let direction = separation / separation.length().powf(3.0);
+
+            accelerations[i] += gravitational_parameters[j] * direction;
+            accelerations[j] -= gravitational_parameters[i] * direction;
+        }
+    }
+
+    accelerations
+}
+
+fn runge_kutta_4_step(positions: &[DVec3], velocities: &[DVec3], parameters: &[f64], dt: f64) -> (Vec<DVec3>, Vec<DVec3>) {
+    let offset = |base: &[DVec3], slope: &[DVec3], scale: f64| -> Vec<DVec3> {
+        base.iter().zip(slope).map(|(value, slope)| *value + *slope * scale).collect()
+    };
+
+    let k1_velocity = velocities.to_vec();
+    let k1_acceleration = gravitational_accelerations(positions, parameters);
+
+    let k2_velocity = offset(velocities, &k1_acceleration, dt / 2.0);
+    let k2_acceleration = gravitational_accelerations(&offset(positions, &k1_velocity, dt / 2.0), parameters);
+
+    let k3_velocity = offset(velocities, &k2_acceleration, dt / 2.0);
+    let k3_acceleration = gravitational_accelerations(&offset(positions, &k2_velocity, dt / 2.0), parameters);
+
+    let k4_velocity = offset(velocities, &k3_acceleration, dt);
+    let k4_acceleration = gravitational_accelerations(&offset(positions, &k3_velocity, dt), parameters);
+
+    let combine = |base: &[DVec3], k1: &[DVec3], k2: &[DVec3], k3: &[DVec3], k4: &[DVec3]| -> Vec<DVec3> {
+        (0..base.len())
+            .map(|i| base[i] + (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]) * dt / 6.0)
+            .collect()
+    };
+
+    (
+        combine(positions, &k1_velocity, &k2_velocity, &k3_velocity, &k4_velocity),
+        combine(velocities, &k1_acceleration, &k2_acceleration, &k3_acceleration, &k4_acceleration),
+    )
+}
+
+fn leapfrog_step(positions: &[DVec3], velocities: &[DVec3], parameters: &[f64], dt: f64) -> (Vec<DVec3>, Vec<DVec3>) {
+    let accelerations = gravitational_accelerations(positions, parameters);
+    let half_kick: Vec<DVec3> = velocities.iter().zip(&accelerations).map(|(v, a)| *v + *a * dt / 2.0).collect();
+    let drifted: Vec<DVec3> = positions.iter().zip(&half_kick).map(|(r, v)| *r + *v * dt).collect();
+
+    let accelerations = gravitational_accelerations(&drifted, parameters);
+    let kicked = half_kick.iter().zip(&accelerations).map(|(v, a)| *v + *a * dt / 2.0).collect();
+
+    (drifted, kicked)
+}
+
+#[test]
+fn n_body_integrators_follow_a_circular_orbit() {
+    use std::f64::consts::PI;
+
+    // A massless test particle on a circular orbit of period 2π around a unit parameter
+    let bodies = vec![
+        PointMass::new(OrbitalPositionVector::new(DVec3::ZERO), OrbitalVelocityVector::new(DVec3::ZERO), GravitationalParameter::from_value(1.0)),
+        PointMass::new(OrbitalPositionVector::new(DVec3::Z), OrbitalVelocityVector::new(DVec3::X), GravitationalParameter::from_value(0.0)),
+    ];
+
+    let adaptive = Integrator::DormandPrince(DormandPrince::new(1.0e-10, 1.0e-12));
+    for &(integrator, tolerance) in &[(Integrator::RungeKutta4, 1.0e-8), (Integrator::Leapfrog, 1.0e-4), (adaptive, 1.0e-7)] {
+        let mut simulation = NBodySimulation::new(bodies.clone(), integrator);
+        assert!(simulation.propagate(2.0 * PI, 2.0 * PI / 1000.0));
+
+        let error = (simulation.bodies()[1].position - DVec3::Z).length();
+        assert!(error < tolerance, "{:?}: {}", integrator, error);
+        assert!((simulation.time() - 2.0 * PI).abs() < 1.0e-9);
+    }
+
+    // Two comparable masses conserve energy over many orbits with the symplectic integrator
+    let pair = vec![
+        PointMass::new(OrbitalPositionVector::new(DVec3::Z * -0.5), OrbitalVelocityVector::new(DVec3::X * -0.5), GravitationalParameter::from_value(1.0)),
+        PointMass::new(OrbitalPositionVector::new(DVec3::Z * 0.5), OrbitalVelocityVector::new(DVec3::X * 0.5), GravitationalParameter::from_value(1.0)),
+    ];
+    let mut simulation = NBodySimulation::new(pair, Integrator::Leapfrog);
+    let initial_energy = simulation.total_energy();
+    simulation.propagate(100.0, 0.01);
+    assert!((simulation.total_energy() - initial_energy).abs() < 1.0e-4 * initial_energy.abs());
+}
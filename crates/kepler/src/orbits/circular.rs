@@ -0,0 +1,169 @@
+use std::f64::consts::PI;
+
+use bevy_math::DVec3;
+
+use crate::{Angle, AngularVelocity, EllipticalOrbit, GravitationalParameter, Length, OrbitalPositionVector, OrbitalVelocityVector, Radian, Time, Velocity, orbital_period, perifocal_rotation};
+
+
+/// Largest eccentricity still taken as a circle when converting from an elliptical orbit
+const CIRCULAR_TOLERANCE: f64 = 1.0e-9;
+
+/// An orbit with eccentricity `e = 0`
+///
+/// The body moves at constant angular velocity, so its position is closed-form
+/// and needs no anomaly solve. Meant for moons and stations propagated in bulk.
+#[derive(Debug, Copy, Clone)]
+pub struct CircularOrbit {
+    /// Notation: `r`
+    radius: Length,
+    longitude_of_ascending_node: Angle,
+
+    /// Inclination
+    ///
+    /// Notation: `i`
+    inclination: Angle,
+
+    /// Angle from the ascending node to the body at the epoch, also known as the argument of latitude
+    ///
+    /// Notation: `u`
+    phase: Angle,
+
+    /// Time at which the body is at `phase`
+    epoch: Time,
+
+    /// Orbital period
+    ///
+    /// Notation: `T`
+    period: Time,
+}
+
+impl CircularOrbit {
+    // Constructors
+    pub fn new(
+        radius: Length,
+        longitude_of_ascending_node: Angle,
+        inclination: Angle,
+        phase: Angle,
+        mu: GravitationalParameter,
+    ) -> Self {
+        CircularOrbit {
+            radius,
+            longitude_of_ascending_node,
+            inclination,
+            phase,
+            epoch: Time::new(0.0),
+            period: orbital_period(radius, mu),
+        }
+    }
+
+    /// Circular orbit through the same plane and radius, with the body at the same place at the same epoch
+    ///
+    /// The secular drift of `orbit` is not carried over, the plane of a circular orbit stays put.
+    /// Returns `None` if `orbit` is not a circle.
+    pub fn from_elliptical(orbit: &EllipticalOrbit) -> Option<Self> {
+        if orbit.eccentricity() > CIRCULAR_TOLERANCE {
+            return None;
+        }
+
+        Some(CircularOrbit {
+            radius: orbit.semimajor_axis(),
+            longitude_of_ascending_node: orbit.longitude_of_ascending_node(),
+            inclination: orbit.inclination(),
+            phase: (orbit.argument_of_periapsis() + orbit.true_anomaly()).wrapped(),
+            epoch: orbit.epoch(),
+            period: orbit.period(),
+        })
+    }
+
+    /// Elliptical orbit with `e = 0` that follows the same path,
+    /// with the periapsis placed where the body is at the epoch
+    pub fn to_elliptical(&self) -> EllipticalOrbit {
+        let mut orbit = EllipticalOrbit::new(
+            0.0,
+            self.radius,
+            Angle::new(0.0),
+            self.longitude_of_ascending_node,
+            self.phase,
+            self.inclination,
+            self.gravitational_parameter(),
+        );
+        orbit.set_epoch(self.epoch);
+
+        orbit
+    }
+
+    // Getters
+    pub fn radius(&self) -> Length { self.radius }
+    pub fn longitude_of_ascending_node(&self) -> Angle { self.longitude_of_ascending_node }
+    pub fn inclination(&self) -> Angle { self.inclination }
+    pub fn phase(&self) -> Angle { self.phase }
+    pub fn period(&self) -> Time { self.period }
+    pub fn epoch(&self) -> Time { self.epoch }
+
+    // Setters
+    pub fn set_epoch(&mut self, value: Time) { self.epoch = value; }
+
+    // Average motion of mean anomaly
+    pub fn mean_angular_motion(&self) -> AngularVelocity {
+        Radian::new(2.0 * PI) / self.period
+    }
+
+    /// Standard gravitational parameter of the parent, derived from the period and radius
+    ///
+    /// `μ = n² r³`
+    pub fn gravitational_parameter(&self) -> GravitationalParameter {
+        GravitationalParameter::from_value(self.mean_angular_motion().val().powf(2.0) * self.radius.val().powf(3.0))
+    }
+
+    /// Constant speed along the orbit
+    ///
+    /// `v = sqrt(μ / r)`
+    pub fn speed(&self) -> Velocity {
+        Velocity::new(self.mean_angular_motion().val() * self.radius.val())
+    }
+
+    /// Angle from the ascending node to the body at the given time
+    pub fn argument_of_latitude_at(&self, time: Time) -> Angle {
+        (self.phase + Angle::from(self.mean_angular_motion() * (time - self.epoch))).wrapped()
+    }
+
+    /// Position relative to the parent at the given time
+    pub fn position_at(&self, time: Time) -> OrbitalPositionVector {
+        self.state_vectors_at(time).0
+    }
+
+    /// Position and velocity relative to the parent at the given time
+    pub fn state_vectors_at(&self, time: Time) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        let (sin, cos) = self.argument_of_latitude_at(time).sin_cos();
+        let rotation = perifocal_rotation(*self.longitude_of_ascending_node.val(), *self.inclination.val(), 0.0);
+
+        (
+            OrbitalPositionVector::new(rotation * DVec3::new(sin, 0.0, cos) * *self.radius.val()),
+            OrbitalVelocityVector::new(rotation * DVec3::new(cos, 0.0, -sin) * *self.speed().val()),
+        )
+    }
+}
+
+#[test]
+fn circular_matches_elliptical() {
+    let mu = GravitationalParameter::from_value(4.9e12);
+    let mut circular = CircularOrbit::new(Length::new(1.8e6), Angle::new(0.7), Angle::new(0.3), Angle::new(2.2), mu);
+    circular.set_epoch(Time::new(-500.0));
+    let elliptical = circular.to_elliptical();
+
+    for &time in &[0.0, 300.0, 4.0e3, 1.0e5] {
+        let time = Time::new(time);
+        let (position, velocity) = circular.state_vectors_at(time);
+        let (expected_position, expected_velocity) = elliptical.state_vectors_at(time);
+
+        assert!((*position.val() - *expected_position.val()).length() < 1.0e-6);
+        assert!((*velocity.val() - *expected_velocity.val()).length() < 1.0e-9);
+    }
+
+    let round_trip = CircularOrbit::from_elliptical(&elliptical).unwrap();
+    assert!((round_trip.phase() - circular.phase()).wrapped_signed().val().abs() < 1.0e-12);
+    assert_eq!(round_trip.epoch(), circular.epoch());
+
+    let eccentric = EllipticalOrbit::new(0.1, Length::new(1.8e6), Angle::new(0.0), Angle::new(0.7), Angle::new(2.2), Angle::new(0.3), mu);
+    assert!(CircularOrbit::from_elliptical(&eccentric).is_none());
+}
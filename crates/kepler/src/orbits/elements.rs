@@ -0,0 +1,113 @@
+use std::f64::consts::PI;
+
+use bevy_math::DVec3;
+
+use crate::{Angle, GravitationalParameter, Length, OrbitalPositionVector, OrbitalVelocityVector, perifocal_rotation};
+
+/// The six classical orbital elements of a conic trajectory of any eccentricity
+///
+/// https://en.wikipedia.org/wiki/Orbital_elements
+#[derive(Debug, Copy, Clone)]
+pub struct OrbitalElements {
+    /// Notation: `e`
+    pub eccentricity: f64,
+    /// Negative for hyperbolic trajectories and infinite for parabolic ones
+    ///
+    /// Notation: `a`
+    pub semimajor_axis: Length,
+    /// Defined for every conic, including the parabola
+    ///
+    /// Notation: `p`
+    pub semi_latus_rectum: Length,
+    /// Notation: `i`
+    pub inclination: Angle,
+    /// Notation: `Ω`
+    pub longitude_of_ascending_node: Angle,
+    /// Notation: `ω`
+    pub argument_of_periapsis: Angle,
+    /// Notation: `θ`
+    pub true_anomaly: Angle,
+}
+
+impl OrbitalElements {
+    /// Computes the classical orbital elements from the position and velocity of a body
+    /// relative to its parent with gravitational parameter `mu`.
+    ///
+    /// For equatorial orbits the ascending node is taken along the reference direction,
+    /// and for circular orbits the periapsis is placed at the ascending node.
+    /// `None` for a body moving straight towards or away from the parent, or at rest,
+    /// which has no orbital plane.
+    pub fn from_state_vectors(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, mu: GravitationalParameter) -> Option<Self> {
+        let (r, v) = (*position.val(), *velocity.val());
+        let tolerance = 1.0e-11;
+
+        let angular_momentum = r.cross(v);
+        if angular_momentum.length() <= tolerance * r.length() * v.length() {
+            return None;
+        }
+        let normal = angular_momentum.normalize();
+        let eccentricity_vector = eccentricity_vector(position, velocity, mu);
+        let eccentricity = eccentricity_vector.length();
+        let mu = *mu.val();
+
+        let inclination = normal.y.clamp(-1.0, 1.0).acos();
+
+        // Node line, falling back to the reference direction when the orbit is equatorial
+        let node = DVec3::Y.cross(normal);
+        let (node, longitude_of_ascending_node) = if node.length() > tolerance {
+            (node.normalize(), node.x.atan2(node.z).rem_euclid(2.0 * PI))
+        } else {
+            (DVec3::Z, 0.0)
+        };
+        let in_plane = normal.cross(node);
+
+        let argument_of_periapsis = if eccentricity > tolerance {
+            eccentricity_vector.dot(in_plane).atan2(eccentricity_vector.dot(node)).rem_euclid(2.0 * PI)
+        } else {
+            0.0
+        };
+        let argument_of_latitude = r.dot(in_plane).atan2(r.dot(node));
+        let true_anomaly = (argument_of_latitude - argument_of_periapsis).rem_euclid(2.0 * PI);
+
+        Some(OrbitalElements {
+            eccentricity,
+            semimajor_axis: Length::new(1.0 / (2.0 / r.length() - v.length_squared() / mu)),
+            semi_latus_rectum: Length::new(angular_momentum.length_squared() / mu),
+            inclination: Angle::new(inclination),
+            longitude_of_ascending_node: Angle::new(longitude_of_ascending_node),
+            argument_of_periapsis: Angle::new(argument_of_periapsis),
+            true_anomaly: Angle::new(true_anomaly),
+        })
+    }
+
+    /// Position and velocity relative to the parent at the stored true anomaly
+    pub fn state_vectors(&self, mu: GravitationalParameter) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        let radius = self.semi_latus_rectum.val() / (1.0 + self.eccentricity * self.true_anomaly.cos());
+        let speed_factor = (mu.val() / self.semi_latus_rectum.val()).sqrt();
+        let (sin, cos) = self.true_anomaly.sin_cos();
+
+        // Perifocal frame: periapsis along +Z, direction of motion at periapsis along +X
+        let position = DVec3::new(sin, 0.0, cos) * radius;
+        let velocity = DVec3::new(self.eccentricity + cos, 0.0, -sin) * speed_factor;
+
+        let rotation = perifocal_rotation(*self.longitude_of_ascending_node.val(), *self.inclination.val(), *self.argument_of_periapsis.val());
+
+        (OrbitalPositionVector::new(rotation * position), OrbitalVelocityVector::new(rotation * velocity))
+    }
+
+    /// True anomaly within `(-π, π]`, as open conics count it
+    ///
+    /// The inbound leg of a hyperbola or parabola lies before the periapsis, at negative anomalies rather than within `(π, 2π)`.
+    pub(crate) fn open_conic_true_anomaly(&self) -> Angle {
+        self.true_anomaly.wrapped_signed()
+    }
+}
+
+/// Vector from the parent towards the periapsis, with the eccentricity as its length
+///
+/// `e = ((v² - μ / r) r - (r · v) v) / μ`
+pub fn eccentricity_vector(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, mu: GravitationalParameter) -> DVec3 {
+    let (r, v, mu) = (*position.val(), *velocity.val(), *mu.val());
+
+    ((v.length_squared() - mu / r.length()) * r - r.dot(v) * v) / mu
+}
@@ -1,44 +1,55 @@
-use std::{f32::consts::PI, ops::Rem};
+use std::{f64::consts::PI, ops::RangeInclusive};
 
-use bevy_math::{Quat, Vec3};
+use bevy_math::{DQuat, Quat, Vec3};
 use bevy_transform::components::Transform;
 use rand::{Rng, thread_rng};
 
-use crate::{calc_true_anomaly, eccentric_anomaly_solver, radius_at_true_anomaly};
+use crate::{Angle, AngularVelocity, DeltaV, GravitationalParameter, Length, OrbitalElements, OrbitalPositionVector, OrbitalVelocityVector, Radian, SecularRates, Time, Trajectory, Velocity, calc_eccentric, calc_eccentric_anomaly, calc_true_anomaly, eccentric_anomaly_solver, orbital_period, perifocal_rotation, radius_at_true_anomaly, semimajor_axis_from_period};
 
 
+#[derive(Debug, Copy, Clone)]
 pub struct EllipticalOrbit {
-    eccentricity: f32,
-    semimajor_axis: f32,
+    eccentricity: f64,
+    semimajor_axis: Length,
 
-    /// True anomaly
+    /// True anomaly at the epoch
     ///
     /// Notation: `θ`
-    true_anomaly: f32,
-    longitude_of_ascending_node: f32,
-    argument_of_periapsis: f32,
+    true_anomaly: Angle,
+    longitude_of_ascending_node: Angle,
+    argument_of_periapsis: Angle,
     
     /// Inclination
     ///
     /// Notation: `i`
-    inclination: f32,
+    inclination: Angle,
 
     /// Orbital period
     ///
     /// Notation: `T`
-    period: f32,
+    period: Time,
+
+    /// Time at which the body is at `true_anomaly`, from which its motion along the orbit
+    /// and the drift of the orientation are measured
+    ///
+    /// Notation: `t₀`
+    epoch: Time,
+
+    /// Drift of the orientation of the orbit since the epoch, such as the precession caused by an oblate parent
+    secular_rates: SecularRates,
 }
 
 impl EllipticalOrbit {
     // Constructors
+    /// The period follows from the semi-major axis and the gravitational parameter `mu` of the parent.
     pub fn new(
-        eccentricity: f32, 
-        semimajor_axis: f32, 
-        true_anomaly: f32, 
-        longitude_of_ascending_node: f32, 
-        argument_of_periapsis: f32, 
-        inclination: f32,
-        period: f32,
+        eccentricity: f64, 
+        semimajor_axis: Length, 
+        true_anomaly: Angle, 
+        longitude_of_ascending_node: Angle, 
+        argument_of_periapsis: Angle, 
+        inclination: Angle,
+        mu: GravitationalParameter,
     ) -> Self {
         EllipticalOrbit {
             eccentricity,
@@ -47,34 +58,69 @@ impl EllipticalOrbit {
             longitude_of_ascending_node,
             argument_of_periapsis,
             inclination,
-            period,
+            period: orbital_period(semimajor_axis, mu),
+            epoch: Time::new(0.0),
+            secular_rates: SecularRates::default(),
         }
     }
 
-    pub fn from_rand() -> Self {
-        let mut rng = thread_rng();
-        EllipticalOrbit {
-            eccentricity: rng.gen_range(0.0..1.0),
-            semimajor_axis: rng.gen_range(0.5..=2.5),
-            longitude_of_ascending_node: rng.gen_range(0.0..PI*2.),
-            inclination: rng.gen_range(0.0..PI*2.),
-            argument_of_periapsis: rng.gen_range(0.0..PI*2.),
-            true_anomaly: rng.gen_range(0.0..PI*2.),
-            period: rng.gen_range(1.0..60.0),
+    /// Computes the orbit from the position and velocity of a body
+    /// relative to its parent with gravitational parameter `mu`.
+    ///
+    /// `None` when the state does not describe a bound orbit (e < 1),
+    /// see `Trajectory::from_state_vectors` for states of any shape.
+    pub fn from_state_vectors(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, mu: GravitationalParameter) -> Option<Self> {
+        let elements = OrbitalElements::from_state_vectors(position, velocity, mu)?;
+
+        if elements.eccentricity < 1.0 {
+            Some(Self::from_elements(elements, mu))
+        } else {
+            None
         }
     }
 
+    pub fn from_elements(elements: OrbitalElements, mu: GravitationalParameter) -> Self {
+        Self::new(
+            elements.eccentricity,
+            elements.semimajor_axis,
+            elements.true_anomaly,
+            elements.longitude_of_ascending_node,
+            elements.argument_of_periapsis,
+            elements.inclination,
+            mu,
+        )
+    }
+
+    /// Random orbit around a parent with gravitational parameter `mu`, with a period within `periods`
+    pub fn from_rand(mu: GravitationalParameter, periods: RangeInclusive<Time>) -> Self {
+        let mut rng = thread_rng();
+        let period = Time::new(rng.gen_range(*periods.start().val()..=*periods.end().val()));
+        Self::new(
+            rng.gen_range(0.0..1.0),
+            semimajor_axis_from_period(period, mu),
+            Angle::new(rng.gen_range(0.0..PI*2.)),
+            Angle::new(rng.gen_range(0.0..PI*2.)),
+            Angle::new(rng.gen_range(0.0..PI*2.)),
+            Angle::new(rng.gen_range(0.0..PI*2.)),
+            mu,
+        )
+    }
+
     // Getters
-    pub fn eccentricity(&self) -> f32 { self.eccentricity }
-    pub fn semimajor_axis(&self) -> f32 { self.semimajor_axis }
-    pub fn true_anomaly(&self) -> f32 { self.true_anomaly }
-    pub fn longitude_of_ascending_node(&self) -> f32 { self.longitude_of_ascending_node }
-    pub fn argument_of_periapsis(&self) -> f32 { self.argument_of_periapsis }
-    pub fn inclination(&self) -> f32 { self.inclination }
-    pub fn period(&self) -> f32 { self.period }
+    pub fn eccentricity(&self) -> f64 { self.eccentricity }
+    pub fn semimajor_axis(&self) -> Length { self.semimajor_axis }
+    pub fn true_anomaly(&self) -> Angle { self.true_anomaly }
+    pub fn longitude_of_ascending_node(&self) -> Angle { self.longitude_of_ascending_node }
+    pub fn argument_of_periapsis(&self) -> Angle { self.argument_of_periapsis }
+    pub fn inclination(&self) -> Angle { self.inclination }
+    pub fn period(&self) -> Time { self.period }
+    pub fn epoch(&self) -> Time { self.epoch }
+    pub fn secular_rates(&self) -> SecularRates { self.secular_rates }
 
     // Setters
-    pub fn set_true_anomaly(&mut self, value: f32) { self.true_anomaly = value; }
+    pub fn set_true_anomaly(&mut self, value: Angle) { self.true_anomaly = value; }
+    pub fn set_epoch(&mut self, value: Time) { self.epoch = value; }
+    pub fn set_secular_rates(&mut self, value: SecularRates) { self.secular_rates = value; }
 
 
     // Orbital Position
@@ -87,21 +133,376 @@ impl EllipticalOrbit {
 
 
     // Average motion of mean anomaly
-    pub fn mean_angular_motion(&self) -> f32 {
-        2.0 * PI / self.period()
+    pub fn mean_angular_motion(&self) -> AngularVelocity {
+        Radian::new(2.0 * PI) / self.period
+    }
+
+    /// Mean anomaly at the epoch, within `(-π, π]`
+    ///
+    /// Notation: `M₀`
+    pub fn mean_anomaly_at_epoch(&self) -> Angle {
+        self.mean_anomaly_at_true_anomaly(self.true_anomaly)
+    }
+
+    /// Time of the periapsis passage closest to the epoch
+    ///
+    /// `τ = t₀ - M₀ / n`
+    pub fn time_of_periapsis(&self) -> Time {
+        self.epoch - self.mean_anomaly_at_epoch().radian() / self.mean_angular_motion()
+    }
+
+    /// Mean anomaly at the given time, within `[0, 2π)`
+    pub fn mean_anomaly(&self, time: Time) -> Angle {
+        Angle::from(self.mean_angular_motion() * (time - self.time_of_periapsis())).wrapped()
+    }
+
+    /// True anomaly at the given time, within `(-π, π]`
+    ///
+    /// Propagated from the state at the epoch with the universal anomaly, like every other `Trajectory`,
+    /// rather than through Kepler's equation, which converges poorly as `e` nears 1.
+    pub fn true_anomaly_at_time(&self, time: Time) -> Angle {
+        Trajectory::Elliptical(*self).true_anomaly_at_time(time)
+    }
+
+    /// Mean anomaly at the given true anomaly, within `(-π, π]`
+    pub fn mean_anomaly_at_true_anomaly(&self, true_anomaly: Angle) -> Angle {
+        Angle::new(calc_eccentric(calc_eccentric_anomaly(self.eccentricity, *true_anomaly.val()), self.eccentricity))
+    }
+
+    /// Time it takes to travel forward from one true anomaly to another
+    ///
+    /// The arrival anomaly is taken as reached on the first pass, unless it is
+    /// one or more whole turns ahead of the departure, which adds one period per revolution.
+    pub fn time_of_flight(&self, from_true_anomaly: Angle, to_true_anomaly: Angle) -> Time {
+        let revolutions = ((to_true_anomaly - from_true_anomaly).val() / (2.0 * PI)).floor().max(0.0);
+        let delta_mean_anomaly = (self.mean_anomaly_at_true_anomaly(to_true_anomaly)
+            - self.mean_anomaly_at_true_anomaly(from_true_anomaly))
+            .wrapped();
+
+        Radian::new(delta_mean_anomaly.val() + revolutions * 2.0 * PI) / self.mean_angular_motion()
+    }
+
+    /// True anomaly reached after travelling `time` from the true anomaly at the epoch
+    pub fn true_anomaly_after(&self, time: Time) -> Angle {
+        let mean_anomaly = self.mean_anomaly_at_epoch() + Angle::from(self.mean_angular_motion() * time);
+        let eccentric_anomaly = eccentric_anomaly_solver(*mean_anomaly.val(), self.eccentricity).anomaly;
+
+        Angle::new(calc_true_anomaly(self.eccentricity, eccentric_anomaly)).wrapped()
+    }
+
+    /// Distance from the focus at closest approach
+    pub fn periapsis(&self) -> Length {
+        self.semimajor_axis * (1.0 - self.eccentricity)
+    }
+
+    /// Distance from the focus at the farthest point
+    pub fn apoapsis(&self) -> Length {
+        self.semimajor_axis * (1.0 + self.eccentricity)
+    }
+
+    /// Longitude of the ascending node at the given time, after the secular drift
+    pub fn longitude_of_ascending_node_at(&self, time: Time) -> Angle {
+        (self.longitude_of_ascending_node + Angle::new(self.secular_rates.longitude_of_ascending_node * (time - self.epoch).val())).wrapped()
+    }
+
+    /// Argument of periapsis at the given time, after the secular drift
+    pub fn argument_of_periapsis_at(&self, time: Time) -> Angle {
+        (self.argument_of_periapsis + Angle::new(self.secular_rates.argument_of_periapsis * (time - self.epoch).val())).wrapped()
+    }
+
+    /// Rotation carrying the orbit from its orientation at the epoch to its drifted orientation at the given time
+    pub fn secular_rotation(&self, time: Time) -> DQuat {
+        let at_epoch = perifocal_rotation(*self.longitude_of_ascending_node.val(), *self.inclination.val(), *self.argument_of_periapsis.val());
+        let at_time = perifocal_rotation(*self.longitude_of_ascending_node_at(time).val(), *self.inclination.val(), *self.argument_of_periapsis_at(time).val());
+
+        at_time * at_epoch.inverse()
     }
 
-    // helper value
-    pub fn mean_anomaly(&self, time: f32) -> f32 {
-        let time_at_periapsis = 0.0;
-        let mean_anom = self.mean_angular_motion() * (time - time_at_periapsis);
+    /// Moves the epoch to the given time, carrying the true anomaly and the drifted orientation along,
+    /// so that the orbit describes the same motion from its new reference
+    pub fn advance_epoch(&mut self, time: Time) {
+        self.true_anomaly = self.true_anomaly_at_time(time).wrapped();
+        self.longitude_of_ascending_node = self.longitude_of_ascending_node_at(time);
+        self.argument_of_periapsis = self.argument_of_periapsis_at(time);
+        self.epoch = time;
+    }
 
-        mean_anom.rem(2.0*PI)
+    /// Standard gravitational parameter of the parent, derived from the period and semi-major axis
+    ///
+    /// `μ = n² a³`
+    pub fn gravitational_parameter(&self) -> GravitationalParameter {
+        GravitationalParameter::from_value(self.mean_angular_motion().val().powf(2.0) * self.semimajor_axis.val().powf(3.0))
     }
 
-    pub fn true_anomaly_at_time(&self, time: f32) -> f32 {
-        let eccentric_anomaly = eccentric_anomaly_solver(self.mean_anomaly(time), self.eccentricity);
+    /// Classical orbital elements with the given true anomaly
+    pub fn elements_at_true_anomaly(&self, true_anomaly: Angle) -> OrbitalElements {
+        OrbitalElements {
+            eccentricity: self.eccentricity,
+            semimajor_axis: self.semimajor_axis,
+            semi_latus_rectum: self.semimajor_axis * (1.0 - self.eccentricity.powf(2.0)),
+            inclination: self.inclination,
+            longitude_of_ascending_node: self.longitude_of_ascending_node,
+            argument_of_periapsis: self.argument_of_periapsis,
+            true_anomaly,
+        }
+    }
+
+    // State Vectors
+    /// Position and velocity relative to the parent at the given true anomaly
+    pub fn state_vectors_at_true_anomaly(&self, true_anomaly: Angle) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        self.elements_at_true_anomaly(true_anomaly).state_vectors(self.gravitational_parameter())
+    }
+
+    /// Position and velocity relative to the parent at the given time, including the secular drift
+    pub fn state_vectors_at(&self, time: Time) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        let mut elements = self.elements_at_true_anomaly(self.true_anomaly_at_time(time));
+        elements.longitude_of_ascending_node = self.longitude_of_ascending_node_at(time);
+        elements.argument_of_periapsis = self.argument_of_periapsis_at(time);
+
+        elements.state_vectors(self.gravitational_parameter())
+    }
+
+    // Velocity
+    /// Magnitude of the angular momentum per unit of mass, constant along the orbit
+    ///
+    /// `h = √(μ a (1 - e²))`
+    pub fn specific_angular_momentum(&self) -> f64 {
+        (self.gravitational_parameter().val() * self.semimajor_axis.val() * (1.0 - self.eccentricity.powf(2.0))).sqrt()
+    }
+
+    /// Speed at the given true anomaly, from the vis-viva equation
+    ///
+    /// `v = √(μ (2 / r - 1 / a))`
+    pub fn speed_at_true_anomaly(&self, true_anomaly: Angle) -> Velocity {
+        let radius = radius_at_true_anomaly(self.eccentricity, *true_anomaly.val(), *self.semimajor_axis.val());
+
+        Velocity::new((self.gravitational_parameter().val() * (2.0 / radius - 1.0 / self.semimajor_axis.val())).sqrt())
+    }
+
+    /// Velocity relative to the parent at the given true anomaly, in the parent's frame of reference
+    pub fn velocity_at_true_anomaly(&self, true_anomaly: Angle) -> OrbitalVelocityVector {
+        self.state_vectors_at_true_anomaly(true_anomaly).1
+    }
+
+    /// Velocity relative to the parent at the given time, in the parent's frame of reference, including the secular drift
+    pub fn velocity_at(&self, time: Time) -> OrbitalVelocityVector {
+        self.state_vectors_at(time).1
+    }
+
+    /// Component of the velocity away from the parent at the given true anomaly
+    ///
+    /// `vᵣ = μ / h e sin θ`
+    pub fn radial_velocity_at_true_anomaly(&self, true_anomaly: Angle) -> Velocity {
+        Velocity::new(self.gravitational_parameter().val() / self.specific_angular_momentum() * self.eccentricity * true_anomaly.sin())
+    }
+
+    /// Component of the velocity perpendicular to the radius, in the direction of motion, at the given true anomaly
+    ///
+    /// `v⊥ = μ / h (1 + e cos θ)`
+    pub fn transverse_velocity_at_true_anomaly(&self, true_anomaly: Angle) -> Velocity {
+        Velocity::new(self.gravitational_parameter().val() / self.specific_angular_momentum() * (1.0 + self.eccentricity * true_anomaly.cos()))
+    }
+
+    /// Angle of the velocity above the local horizontal at the given true anomaly,
+    /// positive while climbing from periapsis to apoapsis
+    ///
+    /// `γ = atan2(e sin θ, 1 + e cos θ)`
+    pub fn flight_path_angle_at_true_anomaly(&self, true_anomaly: Angle) -> Angle {
+        Angle::new((self.eccentricity * true_anomaly.sin()).atan2(1.0 + self.eccentricity * true_anomaly.cos()))
+    }
+
+    /// Osculating trajectory right after an instantaneous burn at the given time
+    ///
+    /// A burn past escape velocity turns the orbit into a parabola or hyperbola.
+    /// The returned trajectory holds its true anomaly at the burn, which is its epoch.
+    /// `None` if the burn leaves the body moving straight towards or away from the parent.
+    pub fn apply_impulse(&self, time: Time, delta_v: DeltaV) -> Option<Trajectory> {
+        let (position, velocity) = self.state_vectors_at(time);
+
+        self.impulse_from_state(position, velocity, delta_v, time)
+    }
+
+    /// Osculating trajectory right after an instantaneous burn at the given true anomaly,
+    /// on the first pass after the epoch
+    pub fn apply_impulse_at_true_anomaly(&self, true_anomaly: Angle, delta_v: DeltaV) -> Option<Trajectory> {
+        let (position, velocity) = self.state_vectors_at_true_anomaly(true_anomaly);
+        let time = self.epoch + self.time_of_flight(self.true_anomaly, true_anomaly);
+
+        self.impulse_from_state(position, velocity, delta_v, time)
+    }
+
+    fn impulse_from_state(&self, position: OrbitalPositionVector, velocity: OrbitalVelocityVector, delta_v: DeltaV, time: Time) -> Option<Trajectory> {
+        let velocity = OrbitalVelocityVector::new(*velocity.val() + delta_v.inertial(position, velocity));
+        let mut trajectory = Trajectory::from_state_vectors(position, velocity, self.gravitational_parameter())?;
+        trajectory.set_epoch(time);
+
+        Some(trajectory)
+    }
+}
+
+#[test]
+fn state_vectors_round_trip() {
+    let original = EllipticalOrbit::new(0.3, Length::new(7.0e6), Angle::new(1.2), Angle::new(0.8), Angle::new(2.1), Angle::new(0.4), GravitationalParameter::from_value(3.986e14));
+    let (position, velocity) = original.state_vectors_at_true_anomaly(original.true_anomaly());
+    let orbit = EllipticalOrbit::from_state_vectors(position, velocity, original.gravitational_parameter()).unwrap();
+
+    assert!((orbit.eccentricity() - original.eccentricity()).abs() < 1.0e-9);
+    assert!(((orbit.semimajor_axis() - original.semimajor_axis()) / original.semimajor_axis()).abs() < 1.0e-9);
+    assert!((orbit.inclination() - original.inclination()).val().abs() < 1.0e-9);
+    assert!((orbit.longitude_of_ascending_node() - original.longitude_of_ascending_node()).val().abs() < 1.0e-9);
+    assert!((orbit.argument_of_periapsis() - original.argument_of_periapsis()).val().abs() < 1.0e-9);
+    assert!((orbit.true_anomaly() - original.true_anomaly()).val().abs() < 1.0e-9);
+    assert!(((orbit.period() - original.period()) / original.period()).abs() < 1.0e-9);
+
+    // Past escape velocity there is no ellipse to return
+    let escaping = OrbitalVelocityVector::new(*velocity.val() * 2.0);
+    assert!(EllipticalOrbit::from_state_vectors(position, escaping, original.gravitational_parameter()).is_none());
+
+    // Falling straight down or at rest there is no orbital plane
+    let falling = OrbitalVelocityVector::new(*position.val() * -1.0e-4);
+    assert!(EllipticalOrbit::from_state_vectors(position, falling, original.gravitational_parameter()).is_none());
+    assert!(Trajectory::from_state_vectors(position, OrbitalVelocityVector::new(bevy_math::DVec3::ZERO), original.gravitational_parameter()).is_none());
+}
+
+#[test]
+fn time_of_flight_round_trip() {
+    let mut orbit = EllipticalOrbit::new(0.6, Length::new(1.0e7), Angle::new(0.0), Angle::new(0.0), Angle::new(0.0), Angle::new(0.0), GravitationalParameter::from_value(3.986e14));
+    let period = orbit.period();
+
+    // Half an orbit from periapsis to apoapsis, whatever the eccentricity
+    assert!((orbit.time_of_flight(Angle::new(0.0), Angle::new(PI)) - period / 2.0).val().abs() < 1.0e-6);
+    assert!((orbit.time_of_flight(Angle::new(0.0), Angle::new(2.0 * PI + PI)) - 1.5 * period).val().abs() < 1.0e-6);
+
+    orbit.set_true_anomaly(Angle::new(5.5));
+    for &target in &[0.0, 0.3, 2.0, 5.6, 6.0] {
+        let time = orbit.time_of_flight(orbit.true_anomaly(), Angle::new(target));
+        let reached = orbit.true_anomaly_after(time);
+
+        assert!(*time.val() >= 0.0 && time < orbit.period());
+        let difference = (reached - Angle::new(target)).wrapped_signed();
+        assert!(difference.val().abs() < 1.0e-9, "target {}: reached {:?}", target, reached);
+    }
+}
+
+#[test]
+fn impulses_rewrite_elements() {
+    let orbit = EllipticalOrbit::new(0.1, Length::new(7.0e6), Angle::new(0.0), Angle::new(0.8), Angle::new(2.1), Angle::new(0.4), GravitationalParameter::from_value(3.986e14));
+    let periapsis = orbit.periapsis();
+
+    // A prograde burn at periapsis raises the apoapsis and keeps the periapsis in place
+    match orbit.apply_impulse(Time::new(0.0), DeltaV::Local { prograde: 200.0, normal: 0.0, radial: 0.0 }).unwrap() {
+        Trajectory::Elliptical(raised) => {
+            assert!(raised.eccentricity() > orbit.eccentricity());
+            assert!((raised.periapsis() - periapsis).val().abs() < 1.0e-3);
+            assert!((raised.inclination() - orbit.inclination()).val().abs() < 1.0e-9);
+        }
+        other => panic!("expected an ellipse, got {:?}", other),
+    }
+
+    // A normal burn tilts the plane, and the same burn as an inertial vector matches it
+    let (position, velocity) = orbit.state_vectors_at(Time::new(1.0e3));
+    let local = DeltaV::Local { prograde: 0.0, normal: 500.0, radial: 0.0 };
+    let inertial = DeltaV::Inertial(local.inertial(position, velocity));
+    match (orbit.apply_impulse(Time::new(1.0e3), local).unwrap(), orbit.apply_impulse(Time::new(1.0e3), inertial).unwrap()) {
+        (Trajectory::Elliptical(tilted), Trajectory::Elliptical(same)) => {
+            assert!((tilted.inclination() - orbit.inclination()).val().abs() > 1.0e-3);
+            assert!((tilted.inclination() - same.inclination()).val().abs() < 1.0e-12);
+        }
+        other => panic!("expected ellipses, got {:?}", other),
+    }
+
+    // Past escape velocity the orbit becomes a hyperbola
+    let escape = orbit.apply_impulse(Time::new(0.0), DeltaV::Local { prograde: 4.0e3, normal: 0.0, radial: 0.0 }).unwrap();
+    assert!(!escape.is_bound());
+    assert!(escape.eccentricity() > 1.0);
+}
+
+#[test]
+fn secular_rates_rotate_the_orbit() {
+    let mut orbit = EllipticalOrbit::new(0.2, Length::new(7.0e6), Angle::new(0.0), Angle::new(0.5), Angle::new(1.0), Angle::new(0.9), GravitationalParameter::from_value(3.986e14));
+    let (frozen, _) = orbit.state_vectors_at(orbit.period());
+
+    let mut body = crate::OrbitalBody::from_sphere(Length::new(6.378e6), crate::Density::new(5.5e3), AngularVelocity::new(0.0));
+    body.j2 = 1.08263e-3;
+    orbit.set_secular_rates(body.oblateness_rates(&orbit));
+
+    // After one period the body is back at periapsis, which has drifted with the orbit
+    let time = orbit.period();
+    let (drifted, _) = orbit.state_vectors_at(time);
+    let expected = crate::perifocal_rotation(*orbit.longitude_of_ascending_node_at(time).val(), *orbit.inclination().val(), *orbit.argument_of_periapsis_at(time).val())
+        * bevy_math::DVec3::Z
+        * *orbit.periapsis().val();
+
+    assert!(orbit.longitude_of_ascending_node_at(time) < orbit.longitude_of_ascending_node());
+    assert!((*drifted.val() - expected).length() < 1.0e-3);
+    assert!((*drifted.val() - *frozen.val()).length() > 1.0);
+}
+
+#[test]
+fn epoch_anchors_the_true_anomaly() {
+    let mut orbit = EllipticalOrbit::new(0.4, Length::new(1.2e7), Angle::new(2.0), Angle::new(0.3), Angle::new(1.1), Angle::new(0.5), GravitationalParameter::from_value(3.986e14));
+    orbit.set_epoch(Time::new(500.0));
+
+    // The body is at its true anomaly at the epoch, not at periapsis
+    let (position, _) = orbit.state_vectors_at(Time::new(500.0));
+    let (expected, _) = orbit.state_vectors_at_true_anomaly(Angle::new(2.0));
+    assert!((*position.val() - *expected.val()).length() < 1.0e-3);
+    let (at_periapsis, _) = orbit.state_vectors_at(orbit.time_of_periapsis());
+    assert!((at_periapsis.val().length() - *orbit.periapsis().val()).abs() < 1.0e-3);
+
+    // Moving the epoch leaves the motion, drift included, where it was
+    orbit.set_secular_rates(SecularRates { longitude_of_ascending_node: -1.0e-6, argument_of_periapsis: 2.0e-6 });
+    let (before, _) = orbit.state_vectors_at(Time::new(9.0e3));
+    orbit.advance_epoch(Time::new(4.0e3));
+    let (after, _) = orbit.state_vectors_at(Time::new(9.0e3));
+    assert_eq!(orbit.epoch(), Time::new(4.0e3));
+    assert!((*after.val() - *before.val()).length() < 1.0e-3);
+
+    // A burn partway along the orbit starts the new orbit from the burn
+    let (burn_position, _) = orbit.state_vectors_at_true_anomaly(Angle::new(0.5));
+    match orbit.apply_impulse_at_true_anomaly(Angle::new(0.5), DeltaV::Local { prograde: 10.0, normal: 0.0, radial: 0.0 }).unwrap() {
+        Trajectory::Elliptical(raised) => {
+            let (position, _) = raised.state_vectors_at(raised.epoch());
+            assert!(raised.epoch() > orbit.epoch());
+            assert!((*position.val() - *burn_position.val()).length() < 1.0e-3);
+        }
+        other => panic!("expected an ellipse, got {:?}", other),
+    }
+}
+
+#[test]
+fn velocity_components_match_the_state_vectors() {
+    let orbit = EllipticalOrbit::new(0.6, Length::new(2.0e7), Angle::new(0.0), Angle::new(0.4), Angle::new(1.0), Angle::new(0.7), GravitationalParameter::from_value(3.986e14));
+
+    for &true_anomaly in &[0.0, 0.8, 2.5, PI, 4.0, 5.9] {
+        let true_anomaly = Angle::new(true_anomaly);
+        let (position, velocity) = orbit.state_vectors_at_true_anomaly(true_anomaly);
+        let radial = *orbit.radial_velocity_at_true_anomaly(true_anomaly).val();
+        let transverse = *orbit.transverse_velocity_at_true_anomaly(true_anomaly).val();
+        let speed = *orbit.speed_at_true_anomaly(true_anomaly).val();
+
+        assert!((speed - velocity.val().length()).abs() < 1.0e-6);
+        assert!((radial - velocity.val().dot(position.val().normalize())).abs() < 1.0e-6);
+        assert!((speed.powf(2.0) - radial.powf(2.0) - transverse.powf(2.0)).abs() < 1.0e-3);
+        assert!((orbit.flight_path_angle_at_true_anomaly(true_anomaly).sin() - radial / speed).abs() < 1.0e-9);
+    }
+
+    // Level flight at both apsides, fastest at periapsis
+    let (periapsis, apoapsis) = (Angle::new(0.0), Angle::new(PI));
+    assert_eq!(*orbit.flight_path_angle_at_true_anomaly(periapsis).val(), 0.0);
+    assert!(orbit.flight_path_angle_at_true_anomaly(apoapsis).val().abs() < 1.0e-12);
+    assert!(orbit.speed_at_true_anomaly(periapsis) > orbit.speed_at_true_anomaly(apoapsis));
+    let at_epoch = *orbit.velocity_at(Time::new(0.0)).val() - *orbit.velocity_at_true_anomaly(periapsis).val();
+    assert!(at_epoch.length() < 1.0e-9, "{}", at_epoch.length());
+}
+
+#[test]
+fn random_orbits_keep_to_the_period_range() {
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let periods = Time::from_days(0.1)..=Time::from_days(30.0);
 
-        calc_true_anomaly(self.eccentricity, eccentric_anomaly)
+    for _ in 0..20 {
+        let period = EllipticalOrbit::from_rand(mu, periods.clone()).period();
+        assert!(*periods.start() * (1.0 - 1.0e-9) <= period && period <= *periods.end() * (1.0 + 1.0e-9), "{:?}", period);
     }
 }
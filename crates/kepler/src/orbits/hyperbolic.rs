@@ -0,0 +1,209 @@
+use crate::{Angle, AngularVelocity, GravitationalParameter, Length, OrbitalElements, OrbitalPositionVector, OrbitalVelocityVector, Radian, Time, Velocity, calc_hyperbolic, calc_hyperbolic_anomaly, calc_true_anomaly_hyperbolic, hyperbolic_anomaly_solver};
+use super::parabolic::PARABOLIC_TOLERANCE;
+
+
+/// An unbound trajectory with eccentricity `e > 1`, such as a flyby or an escape
+///
+/// The body is at `true_anomaly` at the epoch, times before its periapsis passage lie on the inbound leg.
+#[derive(Debug, Copy, Clone)]
+pub struct HyperbolicOrbit {
+    eccentricity: f64,
+    /// Semi-major axis, negative by convention for hyperbolic trajectories
+    ///
+    /// Notation: `a`
+    semimajor_axis: Length,
+
+    /// True anomaly, within the asymptotes `-θ∞ < θ < θ∞`
+    ///
+    /// Notation: `θ`
+    true_anomaly: Angle,
+    longitude_of_ascending_node: Angle,
+    argument_of_periapsis: Angle,
+
+    /// Inclination
+    ///
+    /// Notation: `i`
+    inclination: Angle,
+
+    /// Standard gravitational parameter of the parent
+    ///
+    /// Notation: `μ`
+    gravitational_parameter: GravitationalParameter,
+
+    /// Time at which the body is at `true_anomaly`
+    ///
+    /// Notation: `t₀`
+    epoch: Time,
+}
+
+impl HyperbolicOrbit {
+    // Constructors
+    pub fn new(
+        eccentricity: f64,
+        semimajor_axis: Length,
+        true_anomaly: Angle,
+        longitude_of_ascending_node: Angle,
+        argument_of_periapsis: Angle,
+        inclination: Angle,
+        gravitational_parameter: GravitationalParameter,
+    ) -> Self {
+        HyperbolicOrbit {
+            eccentricity,
+            semimajor_axis,
+            true_anomaly,
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            inclination,
+            gravitational_parameter,
+            epoch: Time::new(0.0),
+        }
+    }
+
+    /// Computes the trajectory from the position and velocity of a body
+    /// relative to its parent with gravitational parameter `mu`.
+    ///
+    /// `None` when the state does not describe a hyperbolic trajectory (e > 1),
+    /// see `Trajectory::from_state_vectors` for states of any shape.
+    pub fn from_state_vectors(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, mu: GravitationalParameter) -> Option<Self> {
+        let elements = OrbitalElements::from_state_vectors(position, velocity, mu)?;
+
+        if elements.eccentricity - 1.0 >= PARABOLIC_TOLERANCE {
+            Some(Self::from_elements(elements, mu))
+        } else {
+            None
+        }
+    }
+
+    pub fn from_elements(elements: OrbitalElements, mu: GravitationalParameter) -> Self {
+        HyperbolicOrbit {
+            eccentricity: elements.eccentricity,
+            semimajor_axis: elements.semimajor_axis,
+            true_anomaly: elements.open_conic_true_anomaly(),
+            longitude_of_ascending_node: elements.longitude_of_ascending_node,
+            argument_of_periapsis: elements.argument_of_periapsis,
+            inclination: elements.inclination,
+            gravitational_parameter: mu,
+            epoch: Time::new(0.0),
+        }
+    }
+
+    // Getters
+    pub fn eccentricity(&self) -> f64 { self.eccentricity }
+    pub fn semimajor_axis(&self) -> Length { self.semimajor_axis }
+    pub fn true_anomaly(&self) -> Angle { self.true_anomaly }
+    pub fn longitude_of_ascending_node(&self) -> Angle { self.longitude_of_ascending_node }
+    pub fn argument_of_periapsis(&self) -> Angle { self.argument_of_periapsis }
+    pub fn inclination(&self) -> Angle { self.inclination }
+    pub fn gravitational_parameter(&self) -> GravitationalParameter { self.gravitational_parameter }
+    pub fn epoch(&self) -> Time { self.epoch }
+
+    // Setters
+    pub fn set_true_anomaly(&mut self, value: Angle) { self.true_anomaly = value; }
+    pub fn set_epoch(&mut self, value: Time) { self.epoch = value; }
+
+    /// Distance from the focus at closest approach
+    pub fn periapsis(&self) -> Length {
+        self.semimajor_axis * (1.0 - self.eccentricity)
+    }
+
+    pub fn semi_latus_rectum(&self) -> Length {
+        self.semimajor_axis * (1.0 - self.eccentricity.powf(2.0))
+    }
+
+    /// True anomaly of the outbound asymptote, the inbound asymptote lies at `-θ∞`
+    ///
+    /// `θ∞ = acos(-1 / e)`
+    pub fn asymptote_true_anomaly(&self) -> Angle {
+        Angle::new((-1.0 / self.eccentricity).acos())
+    }
+
+    /// Angle between the inbound and outbound asymptotes, by which the flyby bends the trajectory
+    ///
+    /// `δ = 2 asin(1 / e)`
+    pub fn turning_angle(&self) -> Angle {
+        Angle::new(2.0 * (1.0 / self.eccentricity).asin())
+    }
+
+    /// Speed left over at infinite distance from the parent
+    ///
+    /// `v∞ = sqrt(-μ / a)`
+    pub fn excess_velocity(&self) -> Velocity {
+        Velocity::new((-self.gravitational_parameter.val() / self.semimajor_axis.val()).sqrt())
+    }
+
+    // Average motion of mean anomaly
+    pub fn mean_angular_motion(&self) -> AngularVelocity {
+        AngularVelocity::new((self.gravitational_parameter.val() / (-self.semimajor_axis.val()).powf(3.0)).sqrt())
+    }
+
+    /// Time of the periapsis passage, after the epoch while the body is inbound
+    ///
+    /// `τ = t₀ - M₀ / n`
+    pub fn time_of_periapsis(&self) -> Time {
+        self.epoch - self.time_since_periapsis(self.true_anomaly)
+    }
+
+    pub fn mean_anomaly(&self, time: Time) -> f64 {
+        *(self.mean_angular_motion() * (time - self.time_of_periapsis())).val()
+    }
+
+    /// Time since periapsis passage at the given true anomaly, negative on the inbound leg
+    pub fn time_since_periapsis(&self, true_anomaly: Angle) -> Time {
+        let hyperbolic_anomaly = calc_hyperbolic_anomaly(self.eccentricity, *true_anomaly.val());
+
+        Radian::new(calc_hyperbolic(hyperbolic_anomaly, self.eccentricity)) / self.mean_angular_motion()
+    }
+
+    pub fn true_anomaly_at_time(&self, time: Time) -> Angle {
+        let hyperbolic_anomaly = hyperbolic_anomaly_solver(self.mean_anomaly(time), self.eccentricity).anomaly;
+
+        Angle::new(calc_true_anomaly_hyperbolic(self.eccentricity, hyperbolic_anomaly))
+    }
+
+    /// Classical orbital elements with the given true anomaly
+    pub fn elements_at_true_anomaly(&self, true_anomaly: Angle) -> OrbitalElements {
+        OrbitalElements {
+            eccentricity: self.eccentricity,
+            semimajor_axis: self.semimajor_axis,
+            semi_latus_rectum: self.semi_latus_rectum(),
+            inclination: self.inclination,
+            longitude_of_ascending_node: self.longitude_of_ascending_node,
+            argument_of_periapsis: self.argument_of_periapsis,
+            true_anomaly,
+        }
+    }
+
+    // State Vectors
+    /// Position and velocity relative to the parent at the given true anomaly
+    pub fn state_vectors_at_true_anomaly(&self, true_anomaly: Angle) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        self.elements_at_true_anomaly(true_anomaly).state_vectors(self.gravitational_parameter)
+    }
+
+    /// Position and velocity relative to the parent at the given time
+    pub fn state_vectors_at(&self, time: Time) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        self.state_vectors_at_true_anomaly(self.true_anomaly_at_time(time))
+    }
+}
+
+#[test]
+fn hyperbolic_time_round_trip() {
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let orbit = HyperbolicOrbit::new(1.8, Length::new(-2.0e7), Angle::new(0.0), Angle::new(0.3), Angle::new(1.1), Angle::new(0.5), mu);
+
+    for &time in &[-5.0e5, -3600.0, 0.0, 120.0, 8.6e4, 1.0e7] {
+        let true_anomaly = orbit.true_anomaly_at_time(Time::new(time));
+
+        assert!(true_anomaly.val().abs() < *orbit.asymptote_true_anomaly().val());
+        assert!((orbit.time_since_periapsis(true_anomaly).val() - time).abs() < 1.0e-6 * time.abs().max(1.0));
+    }
+
+    let (position, velocity) = orbit.state_vectors_at(Time::new(-3600.0));
+    let from_state = HyperbolicOrbit::from_state_vectors(position, velocity, mu).unwrap();
+
+    assert!((from_state.eccentricity() - orbit.eccentricity()).abs() < 1.0e-9);
+    assert!(((from_state.semimajor_axis() - orbit.semimajor_axis()) / orbit.semimajor_axis()).abs() < 1.0e-9);
+    assert!((from_state.true_anomaly() - orbit.true_anomaly_at_time(Time::new(-3600.0))).val().abs() < 1.0e-9);
+
+    // Slowed below escape speed there is no hyperbola to return
+    assert!(HyperbolicOrbit::from_state_vectors(position, OrbitalVelocityVector::new(0.5 * *velocity.val()), mu).is_none());
+}
@@ -0,0 +1,318 @@
+use std::f64::consts::PI;
+
+use bevy_math::{DQuat, DVec3};
+
+use crate::{Angle, EllipticalOrbit, GravitationalParameter, HyperbolicOrbit, OrbitalElements, OrbitalPositionVector, OrbitalVelocityVector, ParabolicOrbit, Time, UniversalOrbit};
+use super::parabolic::PARABOLIC_TOLERANCE;
+
+
+/// An instantaneous change of velocity
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DeltaV {
+    /// Components along the local directions of the orbit at the burn
+    ///
+    /// Prograde follows the velocity, normal follows the angular momentum
+    /// and radial points away from the parent, perpendicular to prograde.
+    Local { prograde: f64, normal: f64, radial: f64 },
+
+    /// Vector in the parent's frame of reference
+    Inertial(DVec3),
+}
+
+impl DeltaV {
+    /// The change of velocity in the parent's frame of reference, for a body at the given state
+    pub fn inertial(&self, position: OrbitalPositionVector, velocity: OrbitalVelocityVector) -> DVec3 {
+        match *self {
+            DeltaV::Local { prograde, normal, radial } => {
+                let (prograde_direction, normal_direction, radial_direction) = maneuver_directions(position, velocity);
+
+                prograde * prograde_direction + normal * normal_direction + radial * radial_direction
+            }
+            DeltaV::Inertial(vector) => vector,
+        }
+    }
+
+    /// Magnitude of the change of velocity, the same in any frame of reference
+    pub fn magnitude(&self) -> f64 {
+        match *self {
+            DeltaV::Local { prograde, normal, radial } => DVec3::new(prograde, normal, radial).length(),
+            DeltaV::Inertial(vector) => vector.length(),
+        }
+    }
+}
+
+/// Prograde, normal and radial unit vectors of a body at the given state
+pub fn maneuver_directions(position: OrbitalPositionVector, velocity: OrbitalVelocityVector) -> (DVec3, DVec3, DVec3) {
+    let prograde = velocity.val().normalize();
+    let normal = position.val().cross(*velocity.val()).normalize();
+    let radial = prograde.cross(normal);
+
+    (prograde, normal, radial)
+}
+
+/// A trajectory of whichever conic section the state vectors describe
+#[derive(Debug, Copy, Clone)]
+pub enum Trajectory {
+    Elliptical(EllipticalOrbit),
+    Parabolic(ParabolicOrbit),
+    Hyperbolic(HyperbolicOrbit),
+}
+
+impl Trajectory {
+    /// Computes the osculating trajectory of a body relative to its parent
+    /// with gravitational parameter `mu`, with its true anomaly at the given state
+    ///
+    /// `None` for radial motion, see `OrbitalElements::from_state_vectors`.
+    pub fn from_state_vectors(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, mu: GravitationalParameter) -> Option<Self> {
+        let elements = OrbitalElements::from_state_vectors(position, velocity, mu)?;
+
+        Some(if (elements.eccentricity - 1.0).abs() < PARABOLIC_TOLERANCE {
+            Trajectory::Parabolic(ParabolicOrbit::from_elements(elements, mu))
+        } else if elements.eccentricity < 1.0 {
+            Trajectory::Elliptical(EllipticalOrbit::from_elements(elements, mu))
+        } else {
+            Trajectory::Hyperbolic(HyperbolicOrbit::from_elements(elements, mu))
+        })
+    }
+
+    pub fn eccentricity(&self) -> f64 {
+        match self {
+            Trajectory::Elliptical(orbit) => orbit.eccentricity(),
+            Trajectory::Parabolic(orbit) => orbit.eccentricity(),
+            Trajectory::Hyperbolic(orbit) => orbit.eccentricity(),
+        }
+    }
+
+    pub fn true_anomaly(&self) -> Angle {
+        match self {
+            Trajectory::Elliptical(orbit) => orbit.true_anomaly(),
+            Trajectory::Parabolic(orbit) => orbit.true_anomaly(),
+            Trajectory::Hyperbolic(orbit) => orbit.true_anomaly(),
+        }
+    }
+
+    /// Span of true anomalies the trajectory covers, open at the asymptotes of unbound ones
+    pub fn true_anomaly_range(&self) -> (Angle, Angle) {
+        match self {
+            Trajectory::Hyperbolic(orbit) => (-orbit.asymptote_true_anomaly(), orbit.asymptote_true_anomaly()),
+            _ => (Angle::new(-PI), Angle::new(PI)),
+        }
+    }
+
+    /// Sets the time at which the body is at the true anomaly of the trajectory
+    ///
+    /// Every orbit is built with the body at its true anomaly at `t = 0`, until its epoch is moved.
+    pub fn set_epoch(&mut self, epoch: Time) {
+        match self {
+            Trajectory::Elliptical(orbit) => orbit.set_epoch(epoch),
+            Trajectory::Parabolic(orbit) => orbit.set_epoch(epoch),
+            Trajectory::Hyperbolic(orbit) => orbit.set_epoch(epoch),
+        }
+    }
+
+    pub fn epoch(&self) -> Time {
+        match self {
+            Trajectory::Elliptical(orbit) => orbit.epoch(),
+            Trajectory::Parabolic(orbit) => orbit.epoch(),
+            Trajectory::Hyperbolic(orbit) => orbit.epoch(),
+        }
+    }
+
+    pub fn is_bound(&self) -> bool {
+        matches!(self, Trajectory::Elliptical(_))
+    }
+
+    /// True anomaly at the given time, within `(-π, π]`
+    ///
+    /// Measured from the periapsis of the trajectory at its epoch, which the secular drift
+    /// of an elliptical orbit carries along with the body.
+    pub fn true_anomaly_at_time(&self, time: Time) -> Angle {
+        let (position, _) = self.universal().state_vectors_at(time);
+        let (periapsis, periapsis_velocity) = self.state_vectors_at_true_anomaly(Angle::new(0.0));
+        let (towards_periapsis, along_periapsis) = (periapsis.val().normalize(), periapsis_velocity.val().normalize());
+
+        Angle::new(position.val().dot(along_periapsis).atan2(position.val().dot(towards_periapsis)))
+    }
+
+    /// Position and velocity relative to the parent at the given time, including the secular drift
+    pub fn state_vectors_at(&self, time: Time) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        let (position, velocity) = self.universal().state_vectors_at(time);
+
+        match self {
+            Trajectory::Elliptical(orbit) => {
+                let rotation = orbit.secular_rotation(time);
+                (OrbitalPositionVector::new(rotation * *position.val()), OrbitalVelocityVector::new(rotation * *velocity.val()))
+            }
+            _ => (position, velocity),
+        }
+    }
+
+    /// The trajectory as state vectors at its epoch, propagated with the universal anomaly
+    ///
+    /// Every shape of conic goes through the same propagator, so the motion stays continuous
+    /// when a burn or a change of parent turns a bound orbit into an unbound one.
+    pub fn universal(&self) -> UniversalOrbit {
+        let (position, velocity) = self.state_vectors_at_true_anomaly(self.true_anomaly());
+
+        UniversalOrbit::new(position, velocity, self.epoch(), self.gravitational_parameter())
+    }
+
+    pub fn gravitational_parameter(&self) -> GravitationalParameter {
+        match self {
+            Trajectory::Elliptical(orbit) => orbit.gravitational_parameter(),
+            Trajectory::Parabolic(orbit) => orbit.gravitational_parameter(),
+            Trajectory::Hyperbolic(orbit) => orbit.gravitational_parameter(),
+        }
+    }
+
+    /// The trajectory at `time` relative to a new parent with gravitational parameter `mu`, as when crossing a sphere of influence
+    ///
+    /// `rotation` turns the axes of the current parent into those of the new one,
+    /// `position` and `velocity` are the state of the current parent relative to the new one, in the axes of the new one.
+    pub fn relative_to(
+        &self,
+        time: Time,
+        rotation: DQuat,
+        position: OrbitalPositionVector,
+        velocity: OrbitalVelocityVector,
+        mu: GravitationalParameter,
+    ) -> Option<Trajectory> {
+        let (own_position, own_velocity) = self.state_vectors_at(time);
+        let mut trajectory = Trajectory::from_state_vectors(
+            OrbitalPositionVector::new(rotation * *own_position.val() + *position.val()),
+            OrbitalVelocityVector::new(rotation * *own_velocity.val() + *velocity.val()),
+            mu,
+        )?;
+        trajectory.set_epoch(time);
+
+        Some(trajectory)
+    }
+
+    /// Osculating trajectory right after an instantaneous burn at the given time
+    ///
+    /// The returned trajectory holds its true anomaly at the burn, which is its epoch.
+    /// `None` if the burn leaves the body moving straight towards or away from the parent.
+    pub fn apply_impulse(&self, time: Time, delta_v: DeltaV) -> Option<Trajectory> {
+        let (position, velocity) = self.state_vectors_at(time);
+        let velocity = OrbitalVelocityVector::new(*velocity.val() + delta_v.inertial(position, velocity));
+        let mut trajectory = Trajectory::from_state_vectors(position, velocity, self.gravitational_parameter())?;
+        trajectory.set_epoch(time);
+
+        Some(trajectory)
+    }
+
+    /// Position and velocity relative to the parent at the given true anomaly
+    pub fn state_vectors_at_true_anomaly(&self, true_anomaly: Angle) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        match self {
+            Trajectory::Elliptical(orbit) => orbit.state_vectors_at_true_anomaly(true_anomaly),
+            Trajectory::Parabolic(orbit) => orbit.state_vectors_at_true_anomaly(true_anomaly),
+            Trajectory::Hyperbolic(orbit) => orbit.state_vectors_at_true_anomaly(true_anomaly),
+        }
+    }
+}
+
+#[test]
+fn epoch_anchors_every_trajectory() {
+    use crate::Length;
+
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let true_anomaly = Angle::new(-0.5);
+    let trajectories = [
+        Trajectory::Elliptical(EllipticalOrbit::new(0.4, Length::new(1.0e7), true_anomaly, Angle::new(0.2), Angle::new(1.0), Angle::new(0.3), mu)),
+        Trajectory::Parabolic(ParabolicOrbit::new(Length::new(7.0e6), true_anomaly, Angle::new(0.2), Angle::new(1.0), Angle::new(0.3), mu)),
+        Trajectory::Hyperbolic(HyperbolicOrbit::new(1.5, Length::new(-1.4e7), true_anomaly, Angle::new(0.2), Angle::new(1.0), Angle::new(0.3), mu)),
+    ];
+
+    for trajectory in trajectories.iter() {
+        let mut trajectory = *trajectory;
+        trajectory.set_epoch(Time::new(5.0e4));
+
+        let (expected, _) = trajectory.state_vectors_at_true_anomaly(true_anomaly);
+        let (position, _) = match trajectory {
+            Trajectory::Elliptical(orbit) => orbit.state_vectors_at(Time::new(5.0e4)),
+            Trajectory::Parabolic(orbit) => orbit.state_vectors_at(Time::new(5.0e4)),
+            Trajectory::Hyperbolic(orbit) => orbit.state_vectors_at(Time::new(5.0e4)),
+        };
+
+        assert!((*position.val() - *expected.val()).length() < 1.0e-6 * expected.val().length());
+    }
+}
+
+#[test]
+fn hyperbolic_orbiter_leaves_its_parent_where_it_was() {
+    use crate::Length;
+
+    // A flyby of the moon, still inbound an hour after its epoch
+    let moon = EllipticalOrbit::new(0.05, Length::new(3.84e8), Angle::new(0.7), Angle::new(0.0), Angle::new(0.4), Angle::new(0.09), GravitationalParameter::from_value(3.986e14));
+    let mut flyby = HyperbolicOrbit::new(1.3, Length::new(-6.0e6), Angle::new(-1.5), Angle::new(0.3), Angle::new(1.2), Angle::new(0.1), GravitationalParameter::from_value(4.905e12));
+    flyby.set_epoch(Time::new(2.0e6));
+    let trajectory = Trajectory::Hyperbolic(flyby);
+    let time = Time::new(2.0e6 + 3600.0);
+
+    // An hour along the inbound leg from where it was at the epoch
+    let true_anomaly = trajectory.true_anomaly_at_time(time);
+    assert!((flyby.time_since_periapsis(true_anomaly) - flyby.time_since_periapsis(Angle::new(-1.5)) - Time::new(3600.0)).val().abs() < 1.0e-6);
+
+    // The frame of the moon is turned against that of the Earth
+    let rotation = DQuat::from_rotation_y(0.4) * DQuat::from_rotation_x(0.09);
+    let (moon_position, moon_velocity) = moon.state_vectors_at(time);
+    let reparented = trajectory.relative_to(time, rotation, moon_position, moon_velocity, GravitationalParameter::from_value(3.986e14)).unwrap();
+    let (position, velocity) = trajectory.state_vectors_at(time);
+    let (new_position, new_velocity) = reparented.state_vectors_at(time);
+
+    assert_eq!(reparented.epoch(), time);
+    assert!((*new_position.val() - (rotation * *position.val() + *moon_position.val())).length() < 1.0e-6 * moon_position.val().length());
+    assert!((*new_velocity.val() - (rotation * *velocity.val() + *moon_velocity.val())).length() < 1.0e-6 * moon_velocity.val().length());
+}
+
+#[test]
+fn retrograde_burn_captures_a_flyby() {
+    use crate::Length;
+
+    let mu = GravitationalParameter::from_value(4.905e12);
+    let mut flyby = HyperbolicOrbit::new(1.3, Length::new(-6.0e6), Angle::new(-1.5), Angle::new(0.3), Angle::new(1.2), Angle::new(0.1), mu);
+    flyby.set_epoch(Time::new(1.0e4));
+    let trajectory = Trajectory::Hyperbolic(flyby);
+    let periapsis_time = flyby.time_of_periapsis();
+
+    let (position, _) = trajectory.state_vectors_at(periapsis_time);
+    let captured = trajectory.apply_impulse(periapsis_time, DeltaV::Local { prograde: -1.0e3, normal: 0.0, radial: 0.0 }).unwrap();
+
+    assert!(captured.is_bound());
+    assert_eq!(captured.epoch(), periapsis_time);
+    assert!((*captured.state_vectors_at(periapsis_time).0.val() - *position.val()).length() < 1.0e-6 * position.val().length());
+}
+
+#[test]
+fn universal_propagation_agrees_with_every_conic() {
+    use crate::{Length, SecularRates};
+
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let mut elliptical = EllipticalOrbit::new(0.4, Length::new(1.0e7), Angle::new(2.8), Angle::new(0.2), Angle::new(1.0), Angle::new(0.3), mu);
+    elliptical.set_secular_rates(SecularRates { longitude_of_ascending_node: -2.0e-7, argument_of_periapsis: 3.0e-7 });
+    let trajectories = [
+        Trajectory::Elliptical(elliptical),
+        Trajectory::Parabolic(ParabolicOrbit::new(Length::new(7.0e6), Angle::new(-1.0), Angle::new(0.2), Angle::new(1.0), Angle::new(0.3), mu)),
+        Trajectory::Hyperbolic(HyperbolicOrbit::new(1.5, Length::new(-1.4e7), Angle::new(-1.0), Angle::new(0.2), Angle::new(1.0), Angle::new(0.3), mu)),
+    ];
+
+    for trajectory in trajectories.iter() {
+        for &time in &[-2.0e3, 0.0, 1.5e3, 4.0e3, 2.0e4] {
+            let time = Time::new(time);
+            let (expected, true_anomaly) = match trajectory {
+                Trajectory::Elliptical(orbit) => {
+                    // Through Kepler's equation, since the orbit itself propagates with the universal anomaly
+                    let true_anomaly = orbit.true_anomaly_after(time - orbit.epoch());
+                    let (position, _) = orbit.state_vectors_at_true_anomaly(true_anomaly);
+                    (OrbitalPositionVector::new(orbit.secular_rotation(time) * *position.val()), true_anomaly)
+                }
+                Trajectory::Parabolic(orbit) => (orbit.state_vectors_at(time).0, orbit.true_anomaly_at_time(time)),
+                Trajectory::Hyperbolic(orbit) => (orbit.state_vectors_at(time).0, orbit.true_anomaly_at_time(time)),
+            };
+            let (position, _) = trajectory.state_vectors_at(time);
+
+            assert!((*position.val() - *expected.val()).length() < 1.0e-6 * expected.val().length(), "{:?} at {:?}", trajectory, time);
+            assert!((trajectory.true_anomaly_at_time(time) - true_anomaly).wrapped_signed().val().abs() < 1.0e-6);
+        }
+    }
+}
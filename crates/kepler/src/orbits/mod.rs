@@ -1,7 +1,17 @@
 mod circular;
+mod elements;
 mod elliptical;
+mod hyperbolic;
+mod maneuver;
+mod parabolic;
+mod universal;
 mod orbit;
 
 pub use circular::*;
+pub use elements::*;
 pub use elliptical::*;
-pub use orbit::*;
\ No newline at end of file
+pub use hyperbolic::*;
+pub use maneuver::*;
+pub use parabolic::*;
+pub use universal::*;
+pub use orbit::*;
@@ -1,42 +1,50 @@
-use std::{f32::consts::PI, ops::Rem};
+use std::f64::consts::PI;
+
+use crate::Angle;
 
 
 pub struct Orbit {
     /// True anomaly
     ///
     /// Notation: `θ`
-    true_anomaly: f32,
+    true_anomaly: f64,
     /// Orbital period
     ///
     /// Notation: `T`
-    period: f32,
+    period: f64,
+    /// Time of a periapsis passage
+    ///
+    /// Notation: `τ`
+    time_of_periapsis: f64,
 }
 
 impl Orbit {
-    pub fn new(true_anomaly: f32, period: f32) -> Self {
+    pub fn new(true_anomaly: f64, period: f64, time_of_periapsis: f64) -> Self {
         Orbit {
             true_anomaly,
             period,
+            time_of_periapsis,
         }
     }
 
     // Getters
-    pub fn true_anomaly(&self) -> f32 { self.true_anomaly }
-    pub fn period(&self) -> f32 { self.period }
+    pub fn true_anomaly(&self) -> f64 { self.true_anomaly }
+    pub fn period(&self) -> f64 { self.period }
+    pub fn time_of_periapsis(&self) -> f64 { self.time_of_periapsis }
 
     // Setters
-    pub fn set_true_anomaly(&mut self, value: f32) { self.true_anomaly = value; }
+    pub fn set_true_anomaly(&mut self, value: f64) { self.true_anomaly = value; }
+    pub fn set_time_of_periapsis(&mut self, value: f64) { self.time_of_periapsis = value; }
 
     // Average motion of mean anomaly
-    pub fn mean_angular_motion(&self) -> f32 {
+    pub fn mean_angular_motion(&self) -> f64 {
         2.0 * PI / self.period()
     }
     
     // helper value
-    pub fn mean_anomaly(&self, time: f32) -> f32 {
-        let time_at_periapsis = 0.0;
-        let mean_anom = self.mean_angular_motion() * (time - time_at_periapsis);
+    pub fn mean_anomaly(&self, time: f64) -> f64 {
+        let mean_anom = Angle::new(self.mean_angular_motion() * (time - self.time_of_periapsis));
 
-        mean_anom.rem(2.0*PI)
+        *mean_anom.wrapped().val()
     }
 }
\ No newline at end of file
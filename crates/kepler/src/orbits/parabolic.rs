@@ -0,0 +1,182 @@
+use crate::{Angle, AngularVelocity, GravitationalParameter, Length, OrbitalElements, OrbitalPositionVector, OrbitalVelocityVector, Radian, Time, barker_solver, calc_barker};
+
+/// Eccentricities this close to 1 are treated as parabolic
+pub(crate) const PARABOLIC_TOLERANCE: f64 = 1.0e-9;
+
+/// A trajectory with eccentricity `e = 1`, the boundary between bound and unbound motion
+///
+/// Propagated with Barker's equation. The body is at `true_anomaly` at the epoch,
+/// times before its periapsis passage lie on the inbound leg.
+#[derive(Debug, Copy, Clone)]
+pub struct ParabolicOrbit {
+    /// Distance from the focus at closest approach
+    ///
+    /// Notation: `q`
+    periapsis: Length,
+
+    /// True anomaly, within `-π < θ < π`
+    ///
+    /// Notation: `θ`
+    true_anomaly: Angle,
+    longitude_of_ascending_node: Angle,
+    argument_of_periapsis: Angle,
+
+    /// Inclination
+    ///
+    /// Notation: `i`
+    inclination: Angle,
+
+    /// Standard gravitational parameter of the parent
+    ///
+    /// Notation: `μ`
+    gravitational_parameter: GravitationalParameter,
+
+    /// Time at which the body is at `true_anomaly`
+    ///
+    /// Notation: `t₀`
+    epoch: Time,
+}
+
+impl ParabolicOrbit {
+    // Constructors
+    pub fn new(
+        periapsis: Length,
+        true_anomaly: Angle,
+        longitude_of_ascending_node: Angle,
+        argument_of_periapsis: Angle,
+        inclination: Angle,
+        gravitational_parameter: GravitationalParameter,
+    ) -> Self {
+        ParabolicOrbit {
+            periapsis,
+            true_anomaly,
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            inclination,
+            gravitational_parameter,
+            epoch: Time::new(0.0),
+        }
+    }
+
+    /// Computes the trajectory from the position and velocity of a body
+    /// relative to its parent with gravitational parameter `mu`.
+    ///
+    /// `None` when the state is not parabolic (e ≈ 1),
+    /// see `Trajectory::from_state_vectors` for states of any shape.
+    pub fn from_state_vectors(position: OrbitalPositionVector, velocity: OrbitalVelocityVector, mu: GravitationalParameter) -> Option<Self> {
+        let elements = OrbitalElements::from_state_vectors(position, velocity, mu)?;
+
+        if (elements.eccentricity - 1.0).abs() < PARABOLIC_TOLERANCE {
+            Some(Self::from_elements(elements, mu))
+        } else {
+            None
+        }
+    }
+
+    pub fn from_elements(elements: OrbitalElements, mu: GravitationalParameter) -> Self {
+        ParabolicOrbit {
+            periapsis: elements.semi_latus_rectum / 2.0,
+            true_anomaly: elements.open_conic_true_anomaly(),
+            longitude_of_ascending_node: elements.longitude_of_ascending_node,
+            argument_of_periapsis: elements.argument_of_periapsis,
+            inclination: elements.inclination,
+            gravitational_parameter: mu,
+            epoch: Time::new(0.0),
+        }
+    }
+
+    // Getters
+    pub fn eccentricity(&self) -> f64 { 1.0 }
+    pub fn periapsis(&self) -> Length { self.periapsis }
+    pub fn true_anomaly(&self) -> Angle { self.true_anomaly }
+    pub fn longitude_of_ascending_node(&self) -> Angle { self.longitude_of_ascending_node }
+    pub fn argument_of_periapsis(&self) -> Angle { self.argument_of_periapsis }
+    pub fn inclination(&self) -> Angle { self.inclination }
+    pub fn gravitational_parameter(&self) -> GravitationalParameter { self.gravitational_parameter }
+    pub fn epoch(&self) -> Time { self.epoch }
+
+    // Setters
+    pub fn set_true_anomaly(&mut self, value: Angle) { self.true_anomaly = value; }
+    pub fn set_epoch(&mut self, value: Time) { self.epoch = value; }
+
+    pub fn semi_latus_rectum(&self) -> Length {
+        2.0 * self.periapsis
+    }
+
+    pub fn radius_at_true_anomaly(&self, true_anomaly: Angle) -> Length {
+        self.semi_latus_rectum() / (1.0 + true_anomaly.cos())
+    }
+
+    /// Average motion of the parabolic mean anomaly
+    ///
+    /// `n = 2 sqrt(μ / p³)`
+    pub fn mean_angular_motion(&self) -> AngularVelocity {
+        AngularVelocity::new(2.0 * (self.gravitational_parameter.val() / self.semi_latus_rectum().val().powf(3.0)).sqrt())
+    }
+
+    /// Time of the periapsis passage, after the epoch while the body is inbound
+    ///
+    /// `τ = t₀ - M₀ / n`
+    pub fn time_of_periapsis(&self) -> Time {
+        self.epoch - self.time_since_periapsis(self.true_anomaly)
+    }
+
+    pub fn mean_anomaly(&self, time: Time) -> f64 {
+        *(self.mean_angular_motion() * (time - self.time_of_periapsis())).val()
+    }
+
+    /// Time since periapsis passage at the given true anomaly, negative on the inbound leg
+    pub fn time_since_periapsis(&self, true_anomaly: Angle) -> Time {
+        Radian::new(calc_barker((true_anomaly.val() / 2.0).tan())) / self.mean_angular_motion()
+    }
+
+    pub fn true_anomaly_at_time(&self, time: Time) -> Angle {
+        Angle::new(2.0 * barker_solver(self.mean_anomaly(time)).atan())
+    }
+
+    /// Classical orbital elements with the given true anomaly
+    pub fn elements_at_true_anomaly(&self, true_anomaly: Angle) -> OrbitalElements {
+        OrbitalElements {
+            eccentricity: 1.0,
+            semimajor_axis: Length::new(f64::INFINITY),
+            semi_latus_rectum: self.semi_latus_rectum(),
+            inclination: self.inclination,
+            longitude_of_ascending_node: self.longitude_of_ascending_node,
+            argument_of_periapsis: self.argument_of_periapsis,
+            true_anomaly,
+        }
+    }
+
+    // State Vectors
+    /// Position and velocity relative to the parent at the given true anomaly
+    pub fn state_vectors_at_true_anomaly(&self, true_anomaly: Angle) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        self.elements_at_true_anomaly(true_anomaly).state_vectors(self.gravitational_parameter)
+    }
+
+    /// Position and velocity relative to the parent at the given time
+    pub fn state_vectors_at(&self, time: Time) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        self.state_vectors_at_true_anomaly(self.true_anomaly_at_time(time))
+    }
+}
+
+#[test]
+fn parabolic_time_round_trip() {
+    let mu = GravitationalParameter::from_value(1.327e20);
+    let orbit = ParabolicOrbit::new(Length::new(7.5e10), Angle::new(0.0), Angle::new(1.0), Angle::new(0.2), Angle::new(2.8), mu);
+
+    for &time in &[-3.0e8, -8.6e4, 0.0, 60.0, 3.0e7] {
+        let true_anomaly = orbit.true_anomaly_at_time(Time::new(time));
+
+        assert!((orbit.time_since_periapsis(true_anomaly).val() - time).abs() < 1.0e-6 * time.abs().max(1.0));
+
+        // Every point of a parabola moves at exactly escape speed
+        let (position, velocity) = orbit.state_vectors_at(Time::new(time));
+        let escape_speed = (2.0 * mu.val() / position.val().length()).sqrt();
+        assert!((velocity.val().length() - escape_speed).abs() / escape_speed < 1.0e-9);
+    }
+
+    // Any slower and the state is bound
+    let (position, velocity) = orbit.state_vectors_at(Time::new(-8.6e4));
+    assert!(ParabolicOrbit::from_state_vectors(position, velocity, mu).is_some());
+    assert!(ParabolicOrbit::from_state_vectors(position, OrbitalVelocityVector::new(0.99 * *velocity.val()), mu).is_none());
+}
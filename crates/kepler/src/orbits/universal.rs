@@ -0,0 +1,139 @@
+use std::f64::consts::PI;
+
+use crate::{Angle, GravitationalParameter, OrbitalElements, OrbitalPositionVector, OrbitalVelocityVector, Time, eccentricity_vector, stumpff_c, stumpff_s, universal_anomaly_solver};
+
+
+/// A trajectory of any eccentricity, defined by its state vectors at an epoch
+///
+/// Propagated with the universal anomaly and Stumpff functions, so circular, elliptical,
+/// parabolic and hyperbolic motion all share one code path. A body whose orbit changes
+/// between bound and unbound stays continuous.
+#[derive(Debug, Copy, Clone)]
+pub struct UniversalOrbit {
+    position: OrbitalPositionVector,
+    velocity: OrbitalVelocityVector,
+
+    /// Time at which `position` and `velocity` are valid
+    epoch: Time,
+
+    /// Standard gravitational parameter of the parent
+    ///
+    /// Notation: `μ`
+    gravitational_parameter: GravitationalParameter,
+}
+
+impl UniversalOrbit {
+    // Constructors
+    pub fn new(
+        position: OrbitalPositionVector,
+        velocity: OrbitalVelocityVector,
+        epoch: Time,
+        gravitational_parameter: GravitationalParameter,
+    ) -> Self {
+        UniversalOrbit {
+            position,
+            velocity,
+            epoch,
+            gravitational_parameter,
+        }
+    }
+
+    // Getters
+    pub fn position(&self) -> OrbitalPositionVector { self.position }
+    pub fn velocity(&self) -> OrbitalVelocityVector { self.velocity }
+    pub fn epoch(&self) -> Time { self.epoch }
+    pub fn gravitational_parameter(&self) -> GravitationalParameter { self.gravitational_parameter }
+
+    /// Osculating orbital elements at the epoch, `None` for radial motion
+    pub fn elements(&self) -> Option<OrbitalElements> {
+        OrbitalElements::from_state_vectors(self.position, self.velocity, self.gravitational_parameter)
+    }
+
+    pub fn eccentricity(&self) -> f64 {
+        eccentricity_vector(self.position, self.velocity, self.gravitational_parameter).length()
+    }
+
+    /// Position and velocity relative to the parent at the given time
+    pub fn state_vectors_at(&self, time: Time) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+        propagate_universal(self.position, self.velocity, self.gravitational_parameter, time - self.epoch)
+    }
+
+    /// True anomaly at the given time, `None` for radial motion
+    pub fn true_anomaly_at_time(&self, time: Time) -> Option<Angle> {
+        self.propagated_to(time).elements().map(|elements| elements.true_anomaly)
+    }
+
+    /// The same trajectory with its epoch moved to the given time
+    pub fn propagated_to(&self, time: Time) -> Self {
+        let (position, velocity) = self.state_vectors_at(time);
+
+        UniversalOrbit {
+            position,
+            velocity,
+            epoch: time,
+            gravitational_parameter: self.gravitational_parameter,
+        }
+    }
+}
+
+/// Advances the state vectors of a body by `time_of_flight`, whatever the eccentricity
+///
+/// Uses the Lagrange coefficients `f`, `g`, `ḟ` and `ġ` in terms of the universal anomaly.
+/// Negative times of flight propagate backwards.
+pub fn propagate_universal(
+    position: OrbitalPositionVector,
+    velocity: OrbitalVelocityVector,
+    mu: GravitationalParameter,
+    time_of_flight: Time,
+) -> (OrbitalPositionVector, OrbitalVelocityVector) {
+    let (r0, v0, mu, time_of_flight) = (*position.val(), *velocity.val(), *mu.val(), *time_of_flight.val());
+    let radius = r0.length();
+    let radial_velocity = r0.dot(v0) / radius;
+    let alpha = 2.0 / radius - v0.length_squared() / mu;
+
+    // Whole revolutions of a bound orbit bring the body back to the same state
+    let time_of_flight = if alpha > 0.0 {
+        time_of_flight % (2.0 * PI / (mu * alpha.powf(3.0)).sqrt())
+    } else {
+        time_of_flight
+    };
+
+    let anomaly = universal_anomaly_solver(radius, radial_velocity, alpha, mu, time_of_flight).anomaly;
+    let z = alpha * anomaly.powf(2.0);
+
+    let f = 1.0 - anomaly.powf(2.0) / radius * stumpff_c(z);
+    let g = time_of_flight - anomaly.powf(3.0) / mu.sqrt() * stumpff_s(z);
+    let r = f * r0 + g * v0;
+
+    let new_radius = r.length();
+    let f_dot = mu.sqrt() / (new_radius * radius) * (z * anomaly * stumpff_s(z) - anomaly);
+    let g_dot = 1.0 - anomaly.powf(2.0) / new_radius * stumpff_c(z);
+    let v = f_dot * r0 + g_dot * v0;
+
+    (OrbitalPositionVector::new(r), OrbitalVelocityVector::new(v))
+}
+
+#[test]
+fn universal_matches_conic_propagators() {
+    use crate::{EllipticalOrbit, HyperbolicOrbit, Length};
+
+    let mu = GravitationalParameter::from_value(3.986e14);
+    let elliptical = EllipticalOrbit::new(0.7, Length::new(2.4e7), Angle::new(0.0), Angle::new(0.4), Angle::new(1.3), Angle::new(0.9), mu);
+    let hyperbolic = HyperbolicOrbit::new(2.5, Length::new(-1.5e7), Angle::new(0.0), Angle::new(2.0), Angle::new(0.6), Angle::new(0.2), mu);
+
+    let (position, velocity) = elliptical.state_vectors_at(Time::new(0.0));
+    let bound = UniversalOrbit::new(position, velocity, Time::new(0.0), mu);
+    let (position, velocity) = hyperbolic.state_vectors_at(Time::new(0.0));
+    let unbound = UniversalOrbit::new(position, velocity, Time::new(0.0), mu);
+
+    for &time in &[-4.0e4, -1.0, 0.0, 900.0, 3.0e4, 2.5e5] {
+        let time = Time::new(time);
+        let expected = elliptical.state_vectors_at(time).0;
+        let actual = bound.state_vectors_at(time).0;
+        assert!((*actual.val() - *expected.val()).length() < 1.0e-6 * expected.val().length(), "t = {:?}", time);
+
+        let expected = hyperbolic.state_vectors_at(time).0;
+        let actual = unbound.state_vectors_at(time).0;
+        assert!((*actual.val() - *expected.val()).length() < 1.0e-6 * expected.val().length(), "t = {:?}", time);
+    }
+}
@@ -0,0 +1,251 @@
+//! Effects that make real orbits drift away from the ideal two-body conic
+//!
+//! https://en.wikipedia.org/wiki/Perturbation_(astronomy)
+
+use bevy_math::{DQuat, DVec3};
+
+use crate::{Density, DormandPrince, EllipticalOrbit, GravitationalParameter, Length, OrbitalPositionVector, OrbitalVelocityVector, StepStatistics, Time};
+
+/// Steady drift of the orientation of an orbit, in radians per unit of time
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct SecularRates {
+    /// Notation: `dΩ/dt`
+    pub longitude_of_ascending_node: f64,
+    /// Notation: `dω/dt`
+    pub argument_of_periapsis: f64,
+}
+
+impl SecularRates {
+    /// Precession caused by the oblateness of the parent, described by its `J2` coefficient
+    ///
+    /// `dΩ/dt = -3/2 n J2 (R / p)² cos i`
+    ///
+    /// `dω/dt = 3/4 n J2 (R / p)² (5 cos² i - 1)`
+    ///
+    /// https://en.wikipedia.org/wiki/Nodal_precession
+    pub fn oblateness(
+        j2: f64,
+        equatorial_radius: f64,
+        semimajor_axis: f64,
+        eccentricity: f64,
+        inclination: f64,
+        mean_angular_motion: f64,
+    ) -> Self {
+        let semi_latus_rectum = semimajor_axis * (1.0 - eccentricity.powf(2.0));
+        let factor = mean_angular_motion * j2 * (equatorial_radius / semi_latus_rectum).powf(2.0);
+        let cos = inclination.cos();
+
+        SecularRates {
+            longitude_of_ascending_node: -1.5 * factor * cos,
+            argument_of_periapsis: 0.75 * factor * (5.0 * cos.powf(2.0) - 1.0),
+        }
+    }
+}
+
+/// Atmosphere whose density falls off exponentially with altitude
+///
+/// `ρ = ρ₀ exp(-h / H)`
+///
+/// https://en.wikipedia.org/wiki/Scale_height
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Atmosphere {
+    /// Density at zero altitude
+    ///
+    /// Notation: `ρ₀`
+    pub surface_density: Density,
+
+    /// Altitude over which the density falls by a factor `e`
+    ///
+    /// Notation: `H`
+    pub scale_height: Length,
+
+    /// Altitude above which the atmosphere is treated as vacuum
+    pub top_altitude: Length,
+}
+
+impl Atmosphere {
+    pub fn new(surface_density: Density, scale_height: Length, top_altitude: Length) -> Self {
+        Atmosphere {
+            surface_density,
+            scale_height,
+            top_altitude,
+        }
+    }
+
+    pub fn density_at(&self, altitude: Length) -> Density {
+        if altitude >= self.top_altitude {
+            Density::new(0.0)
+        } else {
+            self.surface_density * (-altitude.val().max(0.0) / self.scale_height.val()).exp()
+        }
+    }
+
+    /// Deceleration of a body moving through the atmosphere at `relative_velocity`
+    ///
+    /// `a = -½ ρ |v| v / B`, with the ballistic coefficient `B = m / (C_d A)`
+    pub fn drag_acceleration(&self, altitude: Length, relative_velocity: DVec3, ballistic_coefficient: f64) -> DVec3 {
+        -0.5 * self.density_at(altitude).val() * relative_velocity.length() * relative_velocity / ballistic_coefficient
+    }
+}
+
+/// A body other than the parent whose gravity perturbs an orbiter
+///
+/// Only the difference between its pull on the orbiter and on the parent changes the orbit,
+/// since the parent's frame of reference falls towards it as well.
+#[derive(Debug, Copy, Clone)]
+pub struct ThirdBody {
+    pub gravitational_parameter: GravitationalParameter,
+
+    /// Orbit of the third body around the parent,
+    /// or of the parent around the third body when `orbited_by_parent`
+    pub orbit: EllipticalOrbit,
+
+    /// Whether the parent orbits the third body, as a planet does the sun its moons feel
+    pub orbited_by_parent: bool,
+
+    /// Turns the axes `orbit` is given in into those of the parent
+    pub rotation: DQuat,
+}
+
+impl ThirdBody {
+    /// A body orbiting the same parent as the orbiter
+    pub fn sibling(gravitational_parameter: GravitationalParameter, orbit: EllipticalOrbit) -> Self {
+        ThirdBody {
+            gravitational_parameter,
+            orbit,
+            orbited_by_parent: false,
+            rotation: DQuat::IDENTITY,
+        }
+    }
+
+    /// The body the parent orbits, described by the parent's own orbit around it
+    ///
+    /// `rotation` turns the axes of the third body, which `parent_orbit` is given in, into those of the parent.
+    pub fn grandparent(gravitational_parameter: GravitationalParameter, parent_orbit: EllipticalOrbit, rotation: DQuat) -> Self {
+        ThirdBody {
+            gravitational_parameter,
+            orbit: parent_orbit,
+            orbited_by_parent: true,
+            rotation,
+        }
+    }
+
+    /// Position relative to the parent at the given time, in the axes of the parent
+    pub fn position_at(&self, time: Time) -> DVec3 {
+        let (position, _) = self.orbit.state_vectors_at(time);
+
+        self.rotation * if self.orbited_by_parent { -*position.val() } else { *position.val() }
+    }
+
+    /// Perturbing acceleration on an orbiter at `position` relative to the parent, at the given time
+    pub fn acceleration(&self, position: DVec3, time: Time) -> DVec3 {
+        third_body_acceleration(position, self.position_at(time), self.gravitational_parameter)
+    }
+}
+
+/// Tidal acceleration of a third body on an orbiter, both positions relative to the parent
+///
+/// `a = μ₃ ((r₃ - r) / |r₃ - r|³ - r₃ / |r₃|³)`
+///
+/// https://en.wikipedia.org/wiki/Perturbation_(astronomy)#Perturbing_forces
+pub fn third_body_acceleration(position: DVec3, third_body_position: DVec3, third_body_parameter: GravitationalParameter) -> DVec3 {
+    let separation = third_body_position - position;
+
+    *third_body_parameter.val()
+        * (separation / separation.length().powf(3.0) - third_body_position / third_body_position.length().powf(3.0))
+}
+
+/// Advances an orbiter around a parent with gravitational parameter `mu` from `time` by `time_of_flight`,
+/// under the pull of the parent and the tides of `third_bodies`
+///
+/// The osculating trajectory at any point follows from the returned state vectors.
+/// `None` if the integration fails, see `DormandPrince::integrate`.
+pub fn propagate_perturbed(
+    integrator: &DormandPrince,
+    position: OrbitalPositionVector,
+    velocity: OrbitalVelocityVector,
+    mu: GravitationalParameter,
+    third_bodies: &[ThirdBody],
+    time: Time,
+    time_of_flight: Time,
+) -> Option<(OrbitalPositionVector, OrbitalVelocityVector, StepStatistics)> {
+    let acceleration = |elapsed: f64, positions: &[DVec3]| -> Vec<DVec3> {
+        positions
+            .iter()
+            .map(|&r| {
+                let two_body = -*mu.val() * r / r.length().powf(3.0);
+
+                third_bodies.iter().fold(two_body, |sum, third_body| sum + third_body.acceleration(r, time + Time::new(elapsed)))
+            })
+            .collect()
+    };
+
+    let (positions, velocities, statistics) = integrator.integrate(&[*position.val()], &[*velocity.val()], *time_of_flight.val(), acceleration)?;
+
+    Some((OrbitalPositionVector::new(positions[0]), OrbitalVelocityVector::new(velocities[0]), statistics))
+}
+
+#[test]
+fn sun_synchronous_orbit_follows_the_sun() {
+    // 700 km above the Earth at the sun-synchronous inclination
+    let semimajor_axis: f64 = 7.078e6;
+    let mean_angular_motion = (3.986e14 / semimajor_axis.powf(3.0)).sqrt();
+    let rates = SecularRates::oblateness(1.08263e-3, 6.378e6, semimajor_axis, 0.0, 98.19f64.to_radians(), mean_angular_motion);
+
+    let degrees_per_day = rates.longitude_of_ascending_node.to_degrees() * 86400.0;
+    let sun_degrees_per_day = 360.0 / 365.2422;
+    assert!((degrees_per_day - sun_degrees_per_day).abs() < 0.01, "{}", degrees_per_day);
+
+    // At the critical inclination the periapsis stays put
+    let critical = SecularRates::oblateness(1.08263e-3, 6.378e6, semimajor_axis, 0.7, (1.0 / 5.0f64.sqrt()).acos(), mean_angular_motion);
+    assert!(critical.argument_of_periapsis.abs() < 1.0e-18);
+}
+
+#[test]
+fn atmosphere_thins_with_altitude() {
+    let atmosphere = Atmosphere::new(Density::new(1.225), Length::new(8.5e3), Length::new(1.0e5));
+
+    assert!((atmosphere.density_at(Length::new(8.5e3)).val() - 1.225 / std::f64::consts::E).abs() < 1.0e-12);
+    assert_eq!(atmosphere.density_at(Length::new(1.0e5)), Density::new(0.0));
+
+    let velocity = DVec3::new(7.8e3, 0.0, 0.0);
+    let drag = atmosphere.drag_acceleration(Length::new(5.0e4), velocity, 50.0);
+    assert!(drag.dot(velocity) < 0.0);
+    assert!(drag.cross(velocity).length() < 1.0e-12);
+}
+
+#[test]
+fn sun_stretches_the_orbit_of_the_moon() {
+    use crate::{Angle, OrbitalElements};
+
+    let (sun, earth) = (GravitationalParameter::from_value(1.327e20), GravitationalParameter::from_value(3.986e14));
+    let earth_orbit = EllipticalOrbit::new(0.0, Length::new(1.496e11), Angle::new(0.0), Angle::new(0.0), Angle::new(0.0), Angle::new(0.0), sun);
+    let moon_orbit = EllipticalOrbit::new(0.0, Length::new(3.844e8), Angle::new(0.0), Angle::new(0.0), Angle::new(0.0), Angle::new(0.0), earth);
+    let third_body = ThirdBody::grandparent(sun, earth_orbit, DQuat::IDENTITY);
+
+    // Pulled apart along the line to the sun, squeezed across it, with the parent feeling nothing
+    let sun_direction = third_body.position_at(Time::new(0.0)).normalize();
+    let along = third_body.acceleration(sun_direction * 3.844e8, Time::new(0.0));
+    let across = third_body.acceleration(sun_direction.cross(DVec3::Y) * 3.844e8, Time::new(0.0));
+    let tidal = 1.327e20 * 3.844e8 / 1.496e11f64.powf(3.0);
+    assert!((along.dot(sun_direction) - 2.0 * tidal).abs() < 0.02 * tidal);
+    assert!((across.length() - tidal).abs() < 0.02 * tidal);
+    assert_eq!(third_body.acceleration(DVec3::ZERO, Time::new(0.0)), DVec3::ZERO);
+
+    // A parent with turned axes sees the sun turned along with them
+    let rotation = DQuat::from_rotation_x(0.4);
+    let turned = ThirdBody::grandparent(sun, earth_orbit, rotation);
+    assert!((turned.position_at(Time::new(0.0)) - rotation * third_body.position_at(Time::new(0.0))).length() < 1.0e-3);
+
+    // Without perturbers the motion stays on the conic, with the sun the elements drift
+    let integrator = DormandPrince::new(1.0e-11, 1.0e-3);
+    let (position, velocity) = moon_orbit.state_vectors_at(Time::new(0.0));
+    let (expected, _) = moon_orbit.state_vectors_at(Time::new(1.0e6));
+    let (unperturbed, _, _) = propagate_perturbed(&integrator, position, velocity, earth, &[], Time::new(0.0), Time::new(1.0e6)).unwrap();
+    assert!((*unperturbed.val() - *expected.val()).length() < 10.0);
+
+    let (position, velocity, _) = propagate_perturbed(&integrator, position, velocity, earth, &[third_body], Time::new(0.0), Time::new(1.0e6)).unwrap();
+    let osculating = OrbitalElements::from_state_vectors(position, velocity, earth).unwrap();
+    assert!(osculating.eccentricity > 1.0e-3 && osculating.eccentricity < 0.1, "{}", osculating.eccentricity);
+    assert!(((osculating.semimajor_axis - Length::new(3.844e8)) / Length::new(3.844e8)).abs() < 0.05);
+}
@@ -1,7 +1,7 @@
-use bevy_math::{Quat, Vec3};
+use bevy_math::{DQuat, Quat, Vec3};
 use bevy_transform::components::{GlobalTransform, Transform};
 
-use crate::quat_from_axes;
+use crate::{Angle, perifocal_rotation, quat_from_axes};
 
 /// The plane the orbit takes place in.
 ///
@@ -10,19 +10,19 @@ use crate::quat_from_axes;
 pub struct OrbitalPlane {
     /// CCW rotation in radians between the parent
     /// forward and the orbitals plane's forward
-    longitude_of_ascending_node: f32,
+    longitude_of_ascending_node: Angle,
     /// Inclination
     ///
     /// CCW rotation around the local forward, 
     /// to determine the axial tilt of the orbital plane
     /// Notation: `i`
-    inclination: f32,
+    inclination: Angle,
     /// The orientation of the ellipse in the obital plane
-    argument_of_periapsis: f32, 
+    argument_of_periapsis: Angle,
 }
 
 impl OrbitalPlane {
-    pub fn new(ascending_angle: f32, inclination_angle: f32, periapsis_angle: f32) -> Self {
+    pub fn new(ascending_angle: Angle, inclination_angle: Angle, periapsis_angle: Angle) -> Self {
         OrbitalPlane {
             longitude_of_ascending_node: ascending_angle,
             inclination: inclination_angle,
@@ -31,22 +31,24 @@ impl OrbitalPlane {
     }
 
     // Getters
-    pub fn ascending_arg(&self) -> f32 { self.longitude_of_ascending_node }
-    pub fn inclination_arg(&self) -> f32 { self.inclination }
-    pub fn periapsis_arg(&self) -> f32 { self.argument_of_periapsis }
+    pub fn ascending_arg(&self) -> Angle { self.longitude_of_ascending_node }
+    pub fn inclination_arg(&self) -> Angle { self.inclination }
+    pub fn periapsis_arg(&self) -> Angle { self.argument_of_periapsis }
 
     // Setters
-    pub fn set_periapsis_arg(&mut self, new_value: f32) { self.argument_of_periapsis = new_value; }
-    pub fn set_ascending_arg(&mut self, new_value: f32) { self.longitude_of_ascending_node = new_value; }
-    pub fn set_inclination_arg(&mut self, new_value: f32) { self.inclination = new_value; }
+    pub fn set_periapsis_arg(&mut self, new_value: Angle) { self.argument_of_periapsis = new_value; }
+    pub fn set_ascending_arg(&mut self, new_value: Angle) { self.longitude_of_ascending_node = new_value; }
+    pub fn set_inclination_arg(&mut self, new_value: Angle) { self.inclination = new_value; }
 
     pub fn get_rot(&self) -> Quat {
-        let offset_ascending = Quat::from_axis_angle(Vec3::Y, self.ascending_arg());
-        let offset_incl = Quat::from_axis_angle(Vec3::X, self.inclination_arg());
-
-        let result = offset_ascending * offset_incl;
+        self.get_rot_f64().as_f32()
+    }
 
-        result
+    /// Rotation of the orbital plane relative to the parent's frame of reference, in double precision
+    ///
+    /// The argument of periapsis is left out, it is applied within the plane by `get_orbital_position_relative`
+    pub fn get_rot_f64(&self) -> DQuat {
+        perifocal_rotation(*self.ascending_arg().val(), *self.inclination_arg().val(), 0.0)
     }
 
     pub fn get_rotation_global(&self, reference: &GlobalTransform) -> Quat {
@@ -72,7 +74,7 @@ pub trait OrbitalDirectionsLocal {
 
 impl OrbitalDirectionsLocal for OrbitalPlane {
     fn zenith_local(&self, reference: &Transform) -> Vec3 {
-        Quat::from_axis_angle(self.ascending_local(reference), self.inclination) * reference.local_y()
+        Quat::from_axis_angle(self.ascending_local(reference), *self.inclination.val() as f32) * reference.local_y()
     }
 
     fn nadir_local(&self, reference: &Transform) -> Vec3 {
@@ -80,7 +82,7 @@ impl OrbitalDirectionsLocal for OrbitalPlane {
     }
 
     fn ascending_local(&self, reference: &Transform) -> Vec3 {
-        Quat::from_axis_angle(reference.local_y(), self.longitude_of_ascending_node) * reference.local_z()
+        Quat::from_axis_angle(reference.local_y(), *self.longitude_of_ascending_node.val() as f32) * reference.local_z()
     }
 
     fn descending_local(&self, reference: &Transform) -> Vec3 {
@@ -88,7 +90,7 @@ impl OrbitalDirectionsLocal for OrbitalPlane {
     }
 
     fn periapsis_local(&self, reference: &Transform) -> Vec3 {
-        Quat::from_axis_angle(self.zenith_local(reference), self.argument_of_periapsis) * self.ascending_local(reference)
+        Quat::from_axis_angle(self.zenith_local(reference), *self.argument_of_periapsis.val() as f32) * self.ascending_local(reference)
     }
 
     fn apoapsis_local(&self, reference: &Transform) -> Vec3 {
@@ -109,7 +111,7 @@ pub trait OrbitalDirectionsGlobal {
 
 impl OrbitalDirectionsGlobal for OrbitalPlane {
     fn zenith_global(&self, reference: &GlobalTransform) -> Vec3 {
-        Quat::from_axis_angle(self.ascending_global(reference), self.inclination) * reference.local_y()
+        Quat::from_axis_angle(self.ascending_global(reference), *self.inclination.val() as f32) * reference.local_y()
     }
 
     fn nadir_global(&self, reference: &GlobalTransform) -> Vec3 {
@@ -117,7 +119,7 @@ impl OrbitalDirectionsGlobal for OrbitalPlane {
     }
 
     fn ascending_global(&self, reference: &GlobalTransform) -> Vec3 {
-        Quat::from_axis_angle(reference.local_y(), self.longitude_of_ascending_node) * reference.local_z()
+        Quat::from_axis_angle(reference.local_y(), *self.longitude_of_ascending_node.val() as f32) * reference.local_z()
     }
 
     fn descending_global(&self, reference: &GlobalTransform) -> Vec3 {
@@ -125,10 +127,26 @@ impl OrbitalDirectionsGlobal for OrbitalPlane {
     }
 
     fn periapsis_global(&self, reference: &GlobalTransform) -> Vec3 {
-        Quat::from_axis_angle(self.zenith_global(reference), self.argument_of_periapsis) * self.ascending_global(reference)
+        Quat::from_axis_angle(self.zenith_global(reference), *self.argument_of_periapsis.val() as f32) * self.ascending_global(reference)
     }
 
     fn apoapsis_global(&self, reference: &GlobalTransform) -> Vec3 {
         -self.periapsis_global(reference)
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn plane_rotation_agrees_with_perifocal_rotation() {
+    use crate::{Ellipse, EllipticalOrbit, GravitationalParameter, Length, get_orbital_position_relative};
+
+    let orbit = EllipticalOrbit::new(0.4, Length::new(2.0), Angle::new(0.0), Angle::new(0.9), Angle::new(2.3), Angle::new(0.6), GravitationalParameter::from_value(1.0));
+    let plane = OrbitalPlane::new(orbit.longitude_of_ascending_node(), orbit.inclination(), orbit.argument_of_periapsis());
+    let ellipse = Ellipse::from_major(*orbit.semimajor_axis().val(), orbit.eccentricity());
+
+    for &true_anomaly in &[0.0, 1.0, 2.5, -2.0] {
+        let rendered = plane.get_rot_f64() * get_orbital_position_relative(&plane, &ellipse, true_anomaly).as_f64();
+        let (position, _) = orbit.state_vectors_at_true_anomaly(Angle::new(true_anomaly));
+
+        assert!((rendered - *position.val()).length() < 1.0e-5);
+    }
+}
@@ -0,0 +1,38 @@
+/// The shape of a Kepler orbit, described in its own plane with a focus at (X,Y) = (0,0)
+///
+/// `x` points towards the periapsis and `y` along the direction of motion at periapsis.
+///
+/// https://en.wikipedia.org/wiki/Conic_section
+pub trait Conic {
+    /// Half the length of the chord through the focus, parallel to the directrix
+    ///
+    /// Notation: `p`
+    fn semi_latus_rectum(&self) -> f64;
+
+    /// Notation: `e`
+    fn eccentricity(&self) -> f64;
+
+    /// The interval of true anomalies the conic is defined on
+    ///
+    /// Open conics never reach the ends of their range, where the radius goes to infinity.
+    fn true_anomaly_range(&self) -> (f64, f64);
+
+    /// Distance from the focus to the point at true anomaly `θ`
+    ///
+    /// `r = p / (1 + e cos θ)`
+    fn radius_at_true_anomaly(&self, true_anomaly: f64) -> f64 {
+        self.semi_latus_rectum() / (1.0 + self.eccentricity() * true_anomaly.cos())
+    }
+
+    /// Returns the point on the conic with true anomaly `θ`, relative to the focus
+    fn point_at_true_anomaly(&self, true_anomaly: f64) -> (f64, f64) {
+        let radius = self.radius_at_true_anomaly(true_anomaly);
+
+        (radius * true_anomaly.cos(), radius * true_anomaly.sin())
+    }
+
+    /// Whether the conic is a closed curve that the body keeps returning along
+    fn is_closed(&self) -> bool {
+        self.eccentricity() < 1.0
+    }
+}
@@ -1,24 +1,26 @@
-use std::f32::consts::PI;
+use std::f64::consts::PI;
+
+use crate::Conic;
 
 /// An ellipse defined in XY space with the center as (X,Y) = (0,0)
 ///
 /// https://en.wikipedia.org/wiki/Conic_section#Conic_parameters
 pub struct Ellipse {
-    semi_major: f32,
-    semi_minor: f32,
-    eccentricity: f32,
+    semi_major: f64,
+    semi_minor: f64,
+    eccentricity: f64,
 }
 
 impl Ellipse {
     // Constructors
-    pub fn from_major(semi_major: f32, eccentricity: f32) -> Self {
+    pub fn from_major(semi_major: f64, eccentricity: f64) -> Self {
         Ellipse {
             semi_major,
             semi_minor: Self::get_semi_minor_axis(semi_major, eccentricity),
             eccentricity,
         }
     }
-    pub fn from_semi(semi_major: f32, semi_minor: f32) -> Self {
+    pub fn from_semi(semi_major: f64, semi_minor: f64) -> Self {
         Ellipse {
             semi_major,
             semi_minor,
@@ -27,27 +29,27 @@ impl Ellipse {
     }
 
     // Getters
-    pub fn semi_major(&self) -> f32 { self.semi_major }
-    pub fn semi_minor(&self) -> f32 { self.semi_minor }
-    pub fn major_axis(&self) -> f32 { 2.0 * self.semi_major }
-    pub fn minor_axis(&self) -> f32 { 2.0 * self.semi_minor }
-    pub fn eccentricity(&self) -> f32 { self.eccentricity }
+    pub fn semi_major(&self) -> f64 { self.semi_major }
+    pub fn semi_minor(&self) -> f64 { self.semi_minor }
+    pub fn major_axis(&self) -> f64 { 2.0 * self.semi_major }
+    pub fn minor_axis(&self) -> f64 { 2.0 * self.semi_minor }
+    pub fn eccentricity(&self) -> f64 { self.eccentricity }
 
     // Setters
-    pub fn set_eccentricity(&mut self, value: f32) {
+    pub fn set_eccentricity(&mut self, value: f64) {
         self.eccentricity = value;
         self.semi_minor = Ellipse::get_semi_minor_axis(self.semi_major, self.eccentricity);
     }
-    pub fn set_semi_minor(&mut self, value: f32) { self.semi_minor = value; }
-    pub fn set_semi_major(&mut self, value: f32) { self.semi_major = value; }
+    pub fn set_semi_minor(&mut self, value: f64) { self.semi_minor = value; }
+    pub fn set_semi_major(&mut self, value: f64) { self.semi_major = value; }
 
-    pub fn area(&self) -> f32 { PI * self.semi_major * self.semi_minor }
+    pub fn area(&self) -> f64 { PI * self.semi_major * self.semi_minor }
 
     /// Returns the point on the perimeter with angle `a` from the center
     ///
     /// `x` is along the major axis
     /// `y` is along the minor axis
-    pub fn perimeter_point(&self, angle: f32) -> (f32, f32) {
+    pub fn perimeter_point(&self, angle: f64) -> (f64, f64) {
         let x = self.semi_major * angle.cos();
         let y = self.semi_minor * angle.sin();
 
@@ -55,31 +57,56 @@ impl Ellipse {
     }
 
     /// The focal parameter 'p' is the distance from a focus to the corresponding directrix.
-    pub fn focal_parameter(&self) -> f32 {
+    pub fn focal_parameter(&self) -> f64 {
         self.semi_minor.powf(2.0) / (self.semi_major.powf(2.0) - self.semi_minor.powf(2.0)).sqrt()
     }
 
     /// The linear eccentricity 'c' is the distance between the center and a focus.
-    pub fn linear_eccentricity(&self) -> f32 {
+    pub fn linear_eccentricity(&self) -> f64 {
         (self.semi_major.powf(2.0) - self.semi_minor.powf(2.0)).sqrt()
     }
     /// The latus rectum is the chord parallel to the directrix and passing through a focus; its half-length is the semi-latus rectum (ℓ).
-    pub fn semi_latus_rectum(&self) -> f32 {
+    pub fn semi_latus_rectum(&self) -> f64 {
         self.semi_minor.powf(2.0) / self.semi_major
     }
 
     // Statics
     /// Returns the semi-minor axis of an ellipse using the semi-major and eccentricity
-    pub fn get_semi_minor_axis(semi_major_axis: f32, eccentricity: f32) -> f32 {
+    pub fn get_semi_minor_axis(semi_major_axis: f64, eccentricity: f64) -> f64 {
         semi_major_axis * (1.0 - eccentricity.powf(2.0)).sqrt()
     }
 
-    // pub fn get_semi_major_axis(semi_minor_axis: f32, eccentricity: f32) -> f32 {
+    // pub fn get_semi_major_axis(semi_minor_axis: f64, eccentricity: f64) -> f64 {
 
     // }
 
     /// Returns the eccentricity of an ellipse using the semi major and minor axis
-    pub fn get_eccentricity(semi_major: f32, semi_minor: f32) -> f32 {
+    pub fn get_eccentricity(semi_major: f64, semi_minor: f64) -> f64 {
         (1.0 - semi_minor.powf(2.0) / semi_major.powf(2.0)).sqrt()
     }
 }
+
+impl Conic for Ellipse {
+    fn semi_latus_rectum(&self) -> f64 { Ellipse::semi_latus_rectum(self) }
+
+    fn eccentricity(&self) -> f64 { Ellipse::eccentricity(self) }
+
+    fn true_anomaly_range(&self) -> (f64, f64) {
+        (-PI, PI)
+    }
+}
+
+#[test]
+fn ellipse_as_a_conic() {
+    let ellipse = Ellipse::from_major(2.0, 0.5);
+
+    // Periapsis at a (1 - e) and apoapsis at a (1 + e) from the focus, on either side along the x axis
+    let (x, y) = ellipse.point_at_true_anomaly(0.0);
+    assert!((x - 1.0).abs() < 1.0e-12 && y.abs() < 1.0e-12);
+    let (x, y) = ellipse.point_at_true_anomaly(PI);
+    assert!((x + 3.0).abs() < 1.0e-12 && y.abs() < 1.0e-12);
+
+    assert_eq!(ellipse.true_anomaly_range(), (-PI, PI));
+    assert!((ellipse.radius_at_true_anomaly(-1.0) - ellipse.radius_at_true_anomaly(1.0)).abs() < 1.0e-12);
+    assert!(ellipse.is_closed());
+}
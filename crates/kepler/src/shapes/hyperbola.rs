@@ -0,0 +1,68 @@
+use crate::Conic;
+
+/// A hyperbola branch defined in XY space with the center as (X,Y) = (0,0)
+///
+/// https://en.wikipedia.org/wiki/Hyperbola
+#[derive(Debug, Copy, Clone)]
+pub struct Hyperbola {
+    /// Distance from the center to the vertex, positive for the shape
+    semi_major: f64,
+    semi_minor: f64,
+    eccentricity: f64,
+}
+
+impl Hyperbola {
+    // Constructors
+    pub fn from_major(semi_major: f64, eccentricity: f64) -> Self {
+        Hyperbola {
+            semi_major,
+            semi_minor: semi_major * (eccentricity.powf(2.0) - 1.0).sqrt(),
+            eccentricity,
+        }
+    }
+
+    // Getters
+    pub fn semi_major(&self) -> f64 { self.semi_major }
+    pub fn semi_minor(&self) -> f64 { self.semi_minor }
+    pub fn eccentricity(&self) -> f64 { self.eccentricity }
+
+    /// The linear eccentricity 'c' is the distance between the center and a focus.
+    pub fn linear_eccentricity(&self) -> f64 {
+        self.semi_major * self.eccentricity
+    }
+
+    /// True anomaly of the outbound asymptote, the inbound asymptote lies at `-θ∞`
+    pub fn asymptote_true_anomaly(&self) -> f64 {
+        (-1.0 / self.eccentricity).acos()
+    }
+}
+
+impl Conic for Hyperbola {
+    fn semi_latus_rectum(&self) -> f64 {
+        self.semi_minor.powf(2.0) / self.semi_major
+    }
+
+    fn eccentricity(&self) -> f64 { self.eccentricity }
+
+    fn true_anomaly_range(&self) -> (f64, f64) {
+        (-self.asymptote_true_anomaly(), self.asymptote_true_anomaly())
+    }
+}
+
+#[test]
+fn hyperbola_as_a_conic() {
+    let hyperbola = Hyperbola::from_major(2.0, 1.5);
+
+    // `p = a (e² - 1)`, with the periapsis at `a (e - 1)` from the focus along the x axis
+    assert!((hyperbola.semi_latus_rectum() - 2.5).abs() < 1.0e-12);
+    let (x, y) = hyperbola.point_at_true_anomaly(0.0);
+    assert!((x - 1.0).abs() < 1.0e-12 && y.abs() < 1.0e-12);
+
+    // The range ends at the asymptotes, towards which the radius grows without bound
+    let (start, end) = hyperbola.true_anomaly_range();
+    assert_eq!(start, -end);
+    assert!((end - (-1.0 / 1.5_f64).acos()).abs() < 1.0e-12);
+    assert!(hyperbola.radius_at_true_anomaly(end * 0.999) > 100.0);
+    assert!((hyperbola.radius_at_true_anomaly(-1.0) - hyperbola.radius_at_true_anomaly(1.0)).abs() < 1.0e-12);
+    assert!(!hyperbola.is_closed());
+}
@@ -1,3 +1,9 @@
+mod conic;
 mod ellipse;
+mod hyperbola;
+mod parabola;
 
-pub use ellipse::*;
\ No newline at end of file
+pub use conic::*;
+pub use ellipse::*;
+pub use hyperbola::*;
+pub use parabola::*;
@@ -0,0 +1,50 @@
+use std::f64::consts::PI;
+
+use crate::Conic;
+
+/// A parabola defined in XY space with the focus as (X,Y) = (0,0)
+///
+/// https://en.wikipedia.org/wiki/Parabola
+#[derive(Debug, Copy, Clone)]
+pub struct Parabola {
+    /// Distance from the focus to the vertex
+    focal_length: f64,
+}
+
+impl Parabola {
+    // Constructors
+    pub fn new(focal_length: f64) -> Self {
+        Parabola { focal_length }
+    }
+
+    // Getters
+    pub fn focal_length(&self) -> f64 { self.focal_length }
+}
+
+impl Conic for Parabola {
+    fn semi_latus_rectum(&self) -> f64 {
+        2.0 * self.focal_length
+    }
+
+    fn eccentricity(&self) -> f64 { 1.0 }
+
+    fn true_anomaly_range(&self) -> (f64, f64) {
+        (-PI, PI)
+    }
+}
+
+#[test]
+fn parabola_as_a_conic() {
+    let parabola = Parabola::new(1.5);
+
+    // The vertex lies one focal length from the focus, the semi-latus rectum is twice as long
+    let (x, y) = parabola.point_at_true_anomaly(0.0);
+    assert!((x - 1.5).abs() < 1.0e-12 && y.abs() < 1.0e-12);
+    let (x, y) = parabola.point_at_true_anomaly(PI / 2.0);
+    assert!(x.abs() < 1.0e-12 && (y - 3.0).abs() < 1.0e-12);
+
+    // Open towards the back, where the radius grows without bound
+    assert_eq!(parabola.true_anomaly_range(), (-PI, PI));
+    assert!(parabola.radius_at_true_anomaly(PI * 0.999) > 1.0e5);
+    assert!(!parabola.is_closed());
+}
@@ -0,0 +1,197 @@
+//! Planning of coplanar transfers between two orbits around the same parent
+//!
+//! Both orbits are assumed to share the orbital plane of the departure orbit.
+//! Burns are impulsive and tangential to the transfer orbits, taken at their apsides.
+//!
+//! https://en.wikipedia.org/wiki/Hohmann_transfer_orbit
+//! https://en.wikipedia.org/wiki/Bi-elliptic_transfer
+
+use std::f64::consts::PI;
+
+use crate::{Angle, DeltaV, EllipticalOrbit, Length, Time, radius_at_true_anomaly};
+
+
+/// An impulsive burn of a transfer
+#[derive(Debug, Copy, Clone)]
+pub struct TransferBurn {
+    /// Time at which the burn is performed
+    pub time: Time,
+
+    /// Change of velocity, always `DeltaV::Inertial` in the parent's frame of reference
+    pub delta_v: DeltaV,
+}
+
+impl TransferBurn {
+    /// Magnitude of the change of velocity
+    pub fn delta_v_magnitude(&self) -> f64 {
+        self.delta_v.magnitude()
+    }
+}
+
+/// Sequence of burns and the orbits flown between them
+#[derive(Debug, Clone)]
+pub struct TransferPlan {
+    pub burns: Vec<TransferBurn>,
+
+    /// Orbits flown between consecutive burns, each with its true anomaly at the preceding burn
+    pub transfer_orbits: Vec<EllipticalOrbit>,
+}
+
+impl TransferPlan {
+    pub fn total_delta_v(&self) -> f64 {
+        self.burns.iter().map(TransferBurn::delta_v_magnitude).sum()
+    }
+
+    /// Time from the first burn to the last
+    pub fn time_of_flight(&self) -> Time {
+        match (self.burns.first(), self.burns.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => Time::new(0.0),
+        }
+    }
+}
+
+/// Hohmann transfer from `from` to `to`, departing at the first opportunity after `earliest`
+///
+/// The departure burn is taken at the periapsis of `from` when raising the orbit,
+/// and at its apoapsis when lowering it. The arrival is half a transfer orbit later,
+/// at the point of `to` opposite the departure.
+pub fn hohmann_transfer(from: &EllipticalOrbit, to: &EllipticalOrbit, earliest: Time) -> TransferPlan {
+    let departure_anomaly = departure_true_anomaly(from, to);
+    let departure_angle = from.argument_of_periapsis() + departure_anomaly;
+    let departure_radius = orbit_radius(from, departure_anomaly);
+    let arrival_anomaly = departure_angle + Angle::new(PI) - to.argument_of_periapsis();
+    let arrival_radius = orbit_radius(to, arrival_anomaly);
+
+    // Periapsis of the transfer lies on the lower of the two radii
+    let (transfer, transfer_anomaly) = if arrival_radius >= departure_radius {
+        (apsides_orbit(from, departure_radius, arrival_radius, departure_angle, Angle::new(0.0)), Angle::new(0.0))
+    } else {
+        (apsides_orbit(from, arrival_radius, departure_radius, departure_angle + Angle::new(PI), Angle::new(PI)), Angle::new(PI))
+    };
+
+    let departure_time = earliest + from.time_of_flight(from.true_anomaly_at_time(earliest), departure_anomaly);
+    let arrival_time = departure_time + transfer.period() / 2.0;
+
+    TransferPlan {
+        burns: vec![
+            TransferBurn {
+                time: departure_time,
+                delta_v: velocity_change(from, departure_anomaly, &transfer, transfer_anomaly),
+            },
+            TransferBurn {
+                time: arrival_time,
+                delta_v: velocity_change(&transfer, transfer_anomaly + Angle::new(PI), to, arrival_anomaly),
+            },
+        ],
+        transfer_orbits: vec![transfer],
+    }
+}
+
+/// Bi-elliptic transfer from `from` to `to` through an intermediate `apoapsis`,
+/// departing at the first opportunity after `earliest`
+///
+/// The first transfer orbit climbs to `apoapsis`, where the second burn sets up the second
+/// transfer orbit, which comes back down to `to` on the same side as the departure.
+/// Cheaper than a Hohmann transfer for large ratios between the two orbits.
+///
+/// Returns `None` if `apoapsis` lies below either orbit.
+pub fn bi_elliptic_transfer(from: &EllipticalOrbit, to: &EllipticalOrbit, apoapsis: Length, earliest: Time) -> Option<TransferPlan> {
+    let departure_anomaly = departure_true_anomaly(from, to);
+    let departure_angle = from.argument_of_periapsis() + departure_anomaly;
+    let departure_radius = orbit_radius(from, departure_anomaly);
+    let arrival_anomaly = departure_angle - to.argument_of_periapsis();
+    let arrival_radius = orbit_radius(to, arrival_anomaly);
+
+    if apoapsis < departure_radius || apoapsis < arrival_radius {
+        return None;
+    }
+
+    let first = apsides_orbit(from, departure_radius, apoapsis, departure_angle, Angle::new(0.0));
+    let second = apsides_orbit(from, arrival_radius, apoapsis, departure_angle, Angle::new(PI));
+
+    let departure_time = earliest + from.time_of_flight(from.true_anomaly_at_time(earliest), departure_anomaly);
+    let apoapsis_time = departure_time + first.period() / 2.0;
+    let arrival_time = apoapsis_time + second.period() / 2.0;
+
+    Some(TransferPlan {
+        burns: vec![
+            TransferBurn {
+                time: departure_time,
+                delta_v: velocity_change(from, departure_anomaly, &first, Angle::new(0.0)),
+            },
+            TransferBurn {
+                time: apoapsis_time,
+                delta_v: velocity_change(&first, Angle::new(PI), &second, Angle::new(PI)),
+            },
+            TransferBurn {
+                time: arrival_time,
+                delta_v: velocity_change(&second, Angle::new(2.0 * PI), to, arrival_anomaly),
+            },
+        ],
+        transfer_orbits: vec![first, second],
+    })
+}
+
+/// Periapsis of `from` when raising towards `to`, apoapsis when lowering
+fn departure_true_anomaly(from: &EllipticalOrbit, to: &EllipticalOrbit) -> Angle {
+    Angle::new(if to.semimajor_axis() >= from.semimajor_axis() { 0.0 } else { PI })
+}
+
+fn orbit_radius(orbit: &EllipticalOrbit, true_anomaly: Angle) -> Length {
+    Length::new(radius_at_true_anomaly(orbit.eccentricity(), *true_anomaly.val(), *orbit.semimajor_axis().val()))
+}
+
+/// Orbit in the plane of `plane` with its apsides at the given radii,
+/// periapsis at `periapsis_angle` from the ascending node
+fn apsides_orbit(plane: &EllipticalOrbit, periapsis: Length, apoapsis: Length, periapsis_angle: Angle, true_anomaly: Angle) -> EllipticalOrbit {
+    EllipticalOrbit::new(
+        (apoapsis - periapsis) / (apoapsis + periapsis),
+        (periapsis + apoapsis) / 2.0,
+        true_anomaly,
+        plane.longitude_of_ascending_node(),
+        periapsis_angle.wrapped(),
+        plane.inclination(),
+        plane.gravitational_parameter(),
+    )
+}
+
+/// Velocity needed to go from `before` to `after` at the point they share
+fn velocity_change(before: &EllipticalOrbit, before_anomaly: Angle, after: &EllipticalOrbit, after_anomaly: Angle) -> DeltaV {
+    let (_, initial) = before.state_vectors_at_true_anomaly(before_anomaly);
+    let (_, target) = after.state_vectors_at_true_anomaly(after_anomaly);
+
+    DeltaV::Inertial(*target.val() - *initial.val())
+}
+
+#[test]
+fn hohmann_and_bi_elliptic_between_circular_orbits() {
+    use crate::GravitationalParameter;
+
+    let mu: f64 = 3.986e14;
+    let circular = |radius: f64| EllipticalOrbit::new(0.0, Length::new(radius), Angle::new(0.0), Angle::new(0.2), Angle::new(0.5), Angle::new(0.1), GravitationalParameter::from_value(mu));
+    let (r1, r2) = (6.678e6, 4.2164e7);
+
+    let plan = hohmann_transfer(&circular(r1), &circular(r2), Time::new(100.0));
+    let a = (r1 + r2) / 2.0;
+    let first = (mu / r1).sqrt() * ((2.0 * r2 / (r1 + r2)).sqrt() - 1.0);
+    let second = (mu / r2).sqrt() * (1.0 - (2.0 * r1 / (r1 + r2)).sqrt());
+
+    assert!((plan.burns[0].delta_v_magnitude() - first).abs() < 1.0e-6);
+    assert!((plan.burns[1].delta_v_magnitude() - second).abs() < 1.0e-6);
+    assert!((plan.time_of_flight() - Time::new(PI * (a.powf(3.0) / mu).sqrt())).val().abs() < 1.0e-6);
+    assert!(plan.burns[0].time >= Time::new(100.0));
+
+    // Lowering costs the same as raising
+    let lowering = hohmann_transfer(&circular(r2), &circular(r1), Time::new(0.0));
+    assert!((lowering.total_delta_v() - plan.total_delta_v()).abs() < 1.0e-6);
+
+    // Past a radius ratio of about 15.58 a distant enough intermediate apoapsis beats Hohmann
+    let (from, to) = (circular(r1), circular(r1 * 20.0));
+    let hohmann = hohmann_transfer(&from, &to, Time::new(0.0));
+    let bi_elliptic = bi_elliptic_transfer(&from, &to, Length::new(r1 * 200.0), Time::new(0.0)).unwrap();
+    assert!(bi_elliptic.total_delta_v() < hohmann.total_delta_v());
+    assert!(bi_elliptic_transfer(&from, &to, Length::new(r1 * 10.0), Time::new(0.0)).is_none());
+    assert!(bi_elliptic_transfer(&to, &from, Length::new(r1 * 10.0), Time::new(0.0)).is_none());
+    assert!(bi_elliptic_transfer(&from, &to, Length::new(r1 * 0.5), Time::new(0.0)).is_none());
+}
@@ -1,9 +1,9 @@
-use std::f32::consts::PI;
+use std::f64::consts::PI;
 
-use bevy_math::{Mat3, Quat, Vec2, Vec3};
+use bevy_math::{DQuat, DVec3, Mat3, Quat, Vec2, Vec3};
 use bevy_transform::components::Transform;
 
-use crate::{Ellipse, OrbitalPlane};
+use crate::{Conic, OrbitalPlane};
 
 
 pub fn transform_from_axis(right: Vec3, up: Vec3, forward: Vec3) -> Transform {
@@ -18,17 +18,31 @@ pub fn quat_from_axes(right: Vec3, up: Vec3, forward: Vec3) -> Quat {
     Quat::from_rotation_mat3(&Mat3::from_cols(right.normalize(), up.normalize(), forward.normalize()))
 }
 
-pub fn get_orbital_position_relative(plane: &OrbitalPlane, ellipse: &Ellipse, angle: f32) -> Vec3 {
-    let rotation_offset = Quat::from_axis_angle(Vec3::Y, plane.periapsis_arg());
-    let eccentricity_offset = Vec3::new(0.0, 0.0, 1.0) * ellipse.linear_eccentricity();
+/// Rotation from the perifocal frame into the parent's frame of reference
+///
+/// In the perifocal frame the orbit lies in the XZ plane with the periapsis along +Z,
+/// matching the conventions of `OrbitalDirectionsLocal`: the parent's Y axis is the pole
+/// and its Z axis is the reference direction the ascending node is measured from.
+pub fn perifocal_rotation(longitude_of_ascending_node: f64, inclination: f64, argument_of_periapsis: f64) -> DQuat {
+    DQuat::from_axis_angle(DVec3::Y, longitude_of_ascending_node)
+        * DQuat::from_axis_angle(DVec3::Z, inclination)
+        * DQuat::from_axis_angle(DVec3::Y, argument_of_periapsis)
+}
+
+/// Position on the conic at the given true anomaly, relative to the focus in the orbital plane
+pub fn get_orbital_position_relative<C: Conic>(plane: &OrbitalPlane, conic: &C, true_anomaly: f64) -> Vec3 {
+    let rotation_offset = DQuat::from_axis_angle(DVec3::Y, *plane.periapsis_arg().val());
 
-    let point = ellipse.perimeter_point(angle);
-    let vec = Vec3::new(point.1, 0.0, point.0) - eccentricity_offset;
+    let point = conic.point_at_true_anomaly(true_anomaly);
+    let vec = DVec3::new(point.1, 0.0, point.0);
     
-    rotation_offset * vec
+    (rotation_offset * vec).as_f32()
 }
 
-pub fn get_orbital_velocity(semi_major_axis: f32, period: f32) -> f32 {
+/// Mean speed over an orbit, only exact for circular orbits
+///
+/// See `EllipticalOrbit::speed_at_true_anomaly` for the speed at a given point.
+pub fn get_orbital_velocity(semi_major_axis: f64, period: f64) -> f64 {
     (2.0 * PI * semi_major_axis) / period
 }
 
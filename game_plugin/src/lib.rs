@@ -13,6 +13,9 @@ use bevy_inspector_egui::WorldInspectorPlugin;
 use orbit::OrbitPlugin;
 use player::PlayerPlugin;
 
+pub use orbit::{NBodyPropagation, PropagationMode, SimulationScale, SurfaceImpact};
+pub use kepler::{DormandPrince, Integrator};
+
 // use bevy_inspector_egui::WorldInspectorPlugin;
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
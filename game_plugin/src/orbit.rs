@@ -4,11 +4,15 @@ mod world_updater;
 mod orbit_visualizer;
 mod bundles;
 pub(crate) mod components;
+mod simulation_scale;
 
 use world_generator::WorldGeneratorPlugin;
 use world_updater::WorldUpdaterPlugin;
 use orbit_visualizer::OrbitVisualizerPlugin;
 
+pub use world_updater::{NBodyPropagation, PropagationMode, SurfaceImpact};
+pub use simulation_scale::SimulationScale;
+
 use bevy::prelude::{AppBuilder, Plugin};
 use bevy_prototype_debug_lines::DebugLinesPlugin;
 
@@ -17,6 +21,7 @@ pub struct OrbitPlugin;
 impl Plugin for OrbitPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app
+            .init_resource::<SimulationScale>()
             .add_plugin(DebugLinesPlugin)
             .add_plugin(WorldGeneratorPlugin)
             .add_plugin(WorldUpdaterPlugin)
@@ -1,2 +1,43 @@
+use bevy::math::DVec3;
+use kepler::{DeltaV, DormandPrince, Time};
+
 /// Marker component to go with a transform
-pub struct ReferenceFrame;
\ No newline at end of file
+pub struct ReferenceFrame;
+
+/// A burn planned on an orbiting entity, performed once the simulation time reaches `time`
+pub struct ManeuverNode {
+    pub time: Time,
+    pub delta_v: DeltaV,
+}
+
+/// Radius around a reference frame within which its body is the primary of the orbiters
+pub struct SphereOfInfluence {
+    pub radius: f64,
+}
+
+/// Position and velocity of a body propagated numerically, in the frame shared by all such bodies
+pub struct NBodyState {
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// Makes an orbiter feel the atmosphere of the body it orbits
+pub struct Drag {
+    /// Mass over drag coefficient times cross-section, `B = m / (C_d A)`
+    pub ballistic_coefficient: f64,
+}
+
+/// Makes an orbiter feel the tides of the body its primary orbits and of its siblings,
+/// on top of the pull of its primary
+pub struct ThirdBodyPerturbations {
+    /// Tolerances and step limit of the integration of the perturbed motion
+    pub integrator: DormandPrince,
+}
+
+impl Default for ThirdBodyPerturbations {
+    fn default() -> Self {
+        ThirdBodyPerturbations {
+            integrator: DormandPrince::new(1.0e-9, 1.0e-9),
+        }
+    }
+}
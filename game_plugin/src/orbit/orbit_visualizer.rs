@@ -1,10 +1,13 @@
 mod orbit_lines;
 mod debug;
+mod maneuver_preview;
 
 use orbit_lines::draw_orbit_lines;
+use maneuver_preview::draw_maneuver_previews;
 // use debug::{draw_reference_frame, draw_orbital_plane};
 
 use bevy::prelude::{AppBuilder, CoreStage, IntoSystem, Plugin};
+use kepler::{Ellipse, Hyperbola, Parabola};
 
 use self::debug::*;
 
@@ -15,7 +18,19 @@ impl Plugin for OrbitVisualizerPlugin {
         app
             .add_system_to_stage(
                 CoreStage::PostUpdate,
-                draw_orbit_lines.system(),
+                draw_orbit_lines::<Ellipse>.system(),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                draw_orbit_lines::<Hyperbola>.system(),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                draw_orbit_lines::<Parabola>.system(),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                draw_maneuver_previews.system(),
             )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
@@ -0,0 +1,56 @@
+use bevy::{math::Vec3, prelude::{Color, GlobalTransform, Parent, Query, Res, ResMut}};
+use bevy_prototype_debug_lines::DebugLines;
+use kepler::{Angle, EllipticalOrbit, HyperbolicOrbit, ParabolicOrbit};
+
+use crate::orbit::{SimulationScale, components::ManeuverNode};
+use crate::orbit::world_updater::orbit_trajectory;
+use super::orbit_lines::sample_true_anomalies;
+
+
+/// Draws the trajectory each maneuver node will put its entity on, and the direction of the burn
+pub(crate) fn draw_maneuver_previews(
+    query: Query<(&ManeuverNode, &Parent, Option<&EllipticalOrbit>, Option<&ParabolicOrbit>, Option<&HyperbolicOrbit>)>,
+    frames: Query<&GlobalTransform>,
+    mut lines: ResMut<DebugLines>,
+    scale: Res<SimulationScale>,
+) {
+    let color = Color::rgb(0.2, 0.6, 1.0);
+    let steps = 48;
+
+    for (node, parent, elliptical, parabolic, hyperbolic) in query.iter() {
+        let frame = match frames.get(parent.0) {
+            Ok(frame) => frame,
+            Err(_) => continue,
+        };
+        let orbit = match orbit_trajectory(elliptical, parabolic, hyperbolic) {
+            Some(orbit) => orbit,
+            None => continue,
+        };
+        let to_world = |position: Vec3| frame.translation + frame.rotation * position;
+
+        let trajectory = match orbit.apply_impulse(node.time, node.delta_v) {
+            Some(trajectory) => trajectory,
+            None => continue,
+        };
+
+        let (start, end) = trajectory.true_anomaly_range();
+        let positions: Vec<Vec3> = sample_true_anomalies(*start.val(), *end.val(), trajectory.is_bound(), steps)
+            .map(|true_anomaly| {
+                let (position, _) = trajectory.state_vectors_at_true_anomaly(Angle::new(true_anomaly));
+                to_world(scale.position_to_world(*position.val()))
+            })
+            .collect();
+
+        for pair in positions.windows(2) {
+            lines.line_colored(pair[0], pair[1], 0.0, color);
+        }
+        if trajectory.is_bound() {
+            lines.line_colored(positions[positions.len() - 1], positions[0], 0.0, color);
+        }
+
+        let (burn_position, burn_velocity) = orbit.state_vectors_at(node.time);
+        let burn_point = to_world(scale.position_to_world(*burn_position.val()));
+        let burn_direction = node.delta_v.inertial(burn_position, burn_velocity).normalize_or_zero().as_f32();
+        lines.line_colored(burn_point, burn_point + frame.rotation * burn_direction, 0.0, Color::YELLOW);
+    }
+}
@@ -1,26 +1,38 @@
-use std::f32::consts::PI;
-
-use bevy::{math::{Quat, Vec3}, prelude::{Children, Color, Entity, GlobalTransform, Parent, Query, Res, ResMut, Transform, With}};
+use bevy::{ecs::component::Component, math::{Quat, Vec3}, prelude::{Children, Color, Entity, GlobalTransform, Parent, Query, Res, ResMut, Transform, With}};
 use bevy_prototype_debug_lines::DebugLines;
-use kepler::{Ellipse, OrbitalDirectionsGlobal, OrbitalDirectionsLocal, OrbitalPlane, get_orbital_position_relative};
+use kepler::{Conic, OrbitalDirectionsGlobal, OrbitalDirectionsLocal, OrbitalPlane, get_orbital_position_relative};
 
 use crate::player::orbit_picker::OrbitTarget;
 
-pub(crate) fn draw_orbit_lines(
-    query: Query<(&Ellipse, &OrbitalPlane, &GlobalTransform)>,
+/// `steps` true anomalies spread over the range from `start` to `end` of a conic
+///
+/// Open conics stay clear of their asymptotes, where the radius goes to infinity,
+/// closed ones leave out `end`, which is where they started.
+pub(super) fn sample_true_anomalies(start: f64, end: f64, closed: bool, steps: usize) -> impl Iterator<Item = f64> {
+    let (start, step_angle) = if closed {
+        (start, (end - start) / steps as f64)
+    } else {
+        (0.95 * start, 0.95 * (end - start) / (steps - 1) as f64)
+    };
+
+    (0..steps).map(move |n| start + step_angle * n as f64)
+}
+
+pub(crate) fn draw_orbit_lines<C: Conic + Component>(
+    query: Query<(&C, &OrbitalPlane, &GlobalTransform)>,
     mut lines: ResMut<DebugLines>,
     selected_orbit: Res<OrbitTarget>,
 ) {
     let color = Color::rgb(0.8, 0.8, 0.8);
     let steps = 24;
-    let step_angle = 2. * PI / steps as f32;
     // let ring_color = match selected_orbit {
     
-    for (ellipse, orbital_plane, self_global_transform) in query.iter() {
+    for (conic, orbital_plane, self_global_transform) in query.iter() {
         let mut positions: Vec<Vec3> = Vec::new();
 
-        for n in 0..steps {
-            let pos = get_orbital_position_relative(orbital_plane, ellipse, step_angle * n as f32);
+        let (start, end) = conic.true_anomaly_range();
+        for true_anomaly in sample_true_anomalies(start, end, conic.is_closed(), steps) {
+            let pos = get_orbital_position_relative(orbital_plane, conic, true_anomaly);
             let rotated = self_global_transform.rotation * pos;
             let offset = self_global_transform.translation + rotated;
 
@@ -30,10 +42,12 @@ pub(crate) fn draw_orbit_lines(
         for n in 0..positions.len() {
             let current = positions[n];
             let next;
-            if n == positions.len() - 1 {
+            if n < positions.len() - 1 {
+                next = positions[n+1];
+            } else if conic.is_closed() {
                 next = positions[0];
             } else {
-                next = positions[n+1];
+                break;
             }
             
             lines.line_colored(current, next, 0.0, color);
@@ -0,0 +1,71 @@
+use bevy::math::{DVec3, Vec3};
+use kepler::{Length, Time};
+
+
+/// Maps the SI quantities of the simulation to the units of the Bevy world
+///
+/// Insert it as a resource before adding the plugin to change the defaults.
+#[derive(Debug, Copy, Clone)]
+pub struct SimulationScale {
+    /// Distance represented by one world unit
+    pub length: Length,
+
+    /// Simulated time passing in one second of real time
+    pub time: Time,
+
+    /// Factor by which stars are drawn larger than their true size
+    ///
+    /// Kept low enough that a star stays well inside the orbits of its planets.
+    pub star_exaggeration: f64,
+
+    /// Factor by which planets and moons are drawn larger than their true size, to stay visible next to their orbits
+    pub body_exaggeration: f64,
+}
+
+impl Default for SimulationScale {
+    fn default() -> Self {
+        SimulationScale {
+            length: Length::from_astronomical_units(0.1),
+            time: Time::from_days(5.0),
+            star_exaggeration: 20.0,
+            body_exaggeration: 50.0,
+        }
+    }
+}
+
+impl SimulationScale {
+    /// World units spanned by a length
+    pub fn to_world(&self, length: Length) -> f64 {
+        length / self.length
+    }
+
+    /// Length spanned by a number of world units
+    pub fn from_world(&self, units: f64) -> Length {
+        self.length * units
+    }
+
+    /// World translation of a position given in meters
+    pub fn position_to_world(&self, position: DVec3) -> Vec3 {
+        (position / *self.length.val()).as_f32()
+    }
+
+    /// Radius of the sphere drawn for a star of the given radius
+    pub fn star_radius_to_world(&self, radius: Length) -> f32 {
+        (self.to_world(radius) * self.star_exaggeration) as f32
+    }
+
+    /// Radius the sphere drawn for a planet or moon of the given radius stands for
+    pub fn drawn_body_radius(&self, radius: Length) -> Length {
+        radius * self.body_exaggeration
+    }
+
+    /// Radius of the sphere drawn for a planet or moon of the given radius
+    pub fn body_radius_to_world(&self, radius: Length) -> f32 {
+        self.to_world(self.drawn_body_radius(radius)) as f32
+    }
+
+    /// Simulated time passing in the given number of real seconds
+    pub fn simulated_time(&self, real_seconds: f64) -> Time {
+        self.time * real_seconds
+    }
+}
@@ -1,10 +1,11 @@
 use std::f32::consts::PI;
+use std::f64::consts::PI as PI64;
 
-use bevy::{math::{Quat, Vec3}, prelude::{Assets, BuildChildren, Commands, GlobalTransform, Mesh, ResMut, Transform, info}};
-use kepler::{Ellipse, EllipticalOrbitBundle, OrbitalBody, OrbitalBodyBundle, TransformBundle};
+use bevy::{math::{Quat, Vec3}, prelude::{Assets, BuildChildren, Commands, GlobalTransform, Mesh, Res, ResMut, Transform, info}};
+use kepler::{Angle, AngularVelocity, Density, Ellipse, EllipticalOrbit, EllipticalOrbitBundle, Length, OrbitalBody, OrbitalBodyBundle, TransformBundle};
 use rand::Rng;
 
-use crate::orbit::bundles::ReferenceFrameBundle;
+use crate::orbit::{SimulationScale, bundles::ReferenceFrameBundle};
 
 
 pub fn create_ellipse(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>,) {
@@ -16,12 +17,12 @@ pub fn create_ellipse(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>,)
     commands.spawn().insert(Ellipse::from_semi(5.0, 4.89));
 
     let nodes = 24;
-    let angle = 2.0 * PI / nodes as f32;
+    let angle = 2.0 * PI64 / nodes as f64;
     for n in 0..nodes{
-        let n_angle = angle * n as f32;
-        let body = OrbitalBody::from_sphere(0.2, 0.1, -0.3);
+        let n_angle = angle * n as f64;
+        let body = OrbitalBody::from_sphere(Length::new(0.2), Density::new(0.1), AngularVelocity::new(-0.3));
         let (x, y) = ellipse.perimeter_point(n_angle);
-        let position = Vec3::new(x, 0.0, y);
+        let position = Vec3::new(x as f32, 0.0, y as f32);
         info!("Perimeter: {:?}, {:?}", x, y);
         
         commands
@@ -32,78 +33,88 @@ pub fn create_ellipse(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>,)
     }
 }
 
-pub fn create_ellipse_bundles(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>,) {
+pub fn create_ellipse_bundles(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, scale: Res<SimulationScale>) {
 
     let mut rng = rand::thread_rng();
+    // Orbits sized in world units, with periods of a body going around the sun at that distance
+    let mu = OrbitalBody::sun().gravitational_parameter();
+    let orbit = |eccentricity: f64| EllipticalOrbit::new(
+        eccentricity,
+        scale.from_world(5.0),
+        Angle::new(0.0),
+        Angle::new(0.2),
+        Angle::new(0.2),
+        Angle::new(0.2),
+        mu);
 
     let body_one_parent = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            1.0,
-            1.0, 
-            1.0,     
+            Length::new(1.0),
+            Density::new(1.0),
+            AngularVelocity::new(1.0),     
             Transform::default(), 
             &mut meshes))
             .id();
 
     let body_one_child = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            0.5,
-            0.1, 
-            0.1, 
+            Length::new(0.5),
+            Density::new(0.1),
+            AngularVelocity::new(0.1), 
             Transform::default(), 
             &mut meshes))
         .id();
     
     let body_two_parent = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            1.0,
-            1.0, 
-            1.0, 
+            Length::new(1.0),
+            Density::new(1.0),
+            AngularVelocity::new(1.0), 
             Transform::default(), 
             &mut meshes))
         .id();
 
     let body_two_child = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            0.5,
-            0.1, 
-            0.1, 
+            Length::new(0.5),
+            Density::new(0.1),
+            AngularVelocity::new(0.1), 
             Transform::default(), 
             &mut meshes))
         .id();
 
     let body_three_parent = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            1.0,
-            1.0, 
-            1.0, 
+            Length::new(1.0),
+            Density::new(1.0),
+            AngularVelocity::new(1.0), 
             Transform::default(), 
             &mut meshes))
         .id();
     
     let body_three_child = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            0.5,
-            0.1, 
-            0.1, 
+            Length::new(0.5),
+            Density::new(0.1),
+            AngularVelocity::new(0.1), 
             Transform::default(), 
             &mut meshes))
         .id();
 
     let body_one_grandparent = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            0.2,
-            1.0, 
-            1.0, 
+            Length::new(0.2),
+            Density::new(1.0),
+            AngularVelocity::new(1.0), 
             Transform::default(), 
             &mut meshes))
         .id();
     
     let body_one_grandchild = commands
         .spawn_bundle(OrbitalBodyBundle::new(
-            0.5,
-            0.1, 
-            0.1, 
+            Length::new(0.5),
+            Density::new(0.1),
+            AngularVelocity::new(0.1), 
             Transform::default(), 
             &mut meshes))
         .id();
@@ -156,32 +167,17 @@ pub fn create_ellipse_bundles(mut commands: Commands, mut meshes: ResMut<Assets<
     commands.entity(parent_three).push_children(&[body_three_parent]);
 
 
-    let child_one = commands.spawn_bundle(EllipticalOrbitBundle::new(
-        5.0, 
-        0.5,
-        0.2,
-        0.2,
-        0.2))
+    let child_one = commands.spawn_bundle(EllipticalOrbitBundle::new(orbit(0.5), scale.length))
         .id()
     ;
     commands.entity(child_one).push_children(&[body_one_child]);
 
-    let child_two = commands.spawn_bundle(EllipticalOrbitBundle::new(
-        5.0, 
-        0.0,
-        0.2,
-        0.2,
-        0.2))
+    let child_two = commands.spawn_bundle(EllipticalOrbitBundle::new(orbit(0.0), scale.length))
         .id()
     ;
     commands.entity(child_two).push_children(&[body_two_child]);
 
-    let child_three = commands.spawn_bundle(EllipticalOrbitBundle::new(
-        5.0, 
-        0.95,
-        0.2,
-        0.2,
-        0.2))
+    let child_three = commands.spawn_bundle(EllipticalOrbitBundle::new(orbit(0.95), scale.length))
         .id()
     ;
     commands.entity(child_three).push_children(&[body_three_child]);
@@ -1,24 +1,27 @@
 use std::f32::consts::PI;
 
 use bevy::{math::Vec3, prelude::{Res, Transform}};
-use kepler::{OrbitalBody, quat_from_axes};
+use kepler::{AngularVelocity, Density, Length, OrbitalBody, quat_from_axes};
 use rand::Rng;
 use rand_pcg::Pcg64;
 use rand_seeder::Seeder;
 
-use crate::{orbit::orbit_parameters::{OrbitParameters, orbital_position_at_true_anomaly}};
+use crate::orbit::{SimulationScale, orbit_parameters::{OrbitParameters, orbital_position_at_true_anomaly}};
 
 use super::{HierarchyNode, OrbitNode, RootNode, WorldGenerationSettings};
 
 /// Generates the orbits to be used
-pub(super) fn generate_world(settings: Res<WorldGenerationSettings>) -> RootNode {
+///
+/// Bodies and orbits are generated in SI units, the transforms are placed in world units with `scale`.
+pub(super) fn generate_world(settings: Res<WorldGenerationSettings>, scale: &SimulationScale) -> RootNode {
 
     let root_reference = Transform::default();
     let mut rng: Pcg64 = Seeder::from("KEPLER").make_rng();
+    let sun = OrbitalBody::sun();
 
     let solar_system_node = RootNode {
         reference_frame: root_reference,
-        body: OrbitalBody::from_sphere(0.5, 1.0, 1.0),
+        body: sun,
         children: {
             let mut nodes: Vec<HierarchyNode> = Vec::new();
 
@@ -26,14 +29,33 @@ pub(super) fn generate_world(settings: Res<WorldGenerationSettings>) -> RootNode
             // R-O pair can have multiple R-O child
             // Planets
             for _ in 0..3 {
-                let planet = generate_node(&root_reference, &mut rng);
-
-                // 
-                let moon_01 = generate_node(&planet.reference_frame, &mut rng);
-                let moon_02 = generate_node(&planet.reference_frame, &mut rng);
+                let semimajor_axis = Length::from_astronomical_units(rng.gen_range(0.4..=5.0));
+                let body = OrbitalBody::from_sphere(
+                    Length::from_kilometers(rng.gen_range(2_000.0..=70_000.0)),
+                    Density::new(rng.gen_range(1_300.0..=5_500.0)),
+                    AngularVelocity::new(rng.gen_range(1.0e-5..=2.0e-4)),
+                );
+                let planet = generate_node(&root_reference, semimajor_axis, body, scale, &mut rng);
+
+                // Moons stay well within the sphere of influence of their planet,
+                // and far enough out that the drawn spheres of the two stay apart
+                let sphere_of_influence = body.sphere_of_influence(&sun, semimajor_axis);
                 let mut planet_children: Vec<HierarchyNode> = Vec::new();
-                planet_children.push(HierarchyNode { node: moon_01,  children: None });
-                planet_children.push(HierarchyNode { node: moon_02,  children: None });
+                for _ in 0..2 {
+                    let moon_body = OrbitalBody::from_sphere(
+                        Length::from_kilometers(rng.gen_range(200.0..=2_500.0)),
+                        Density::new(rng.gen_range(2_000.0..=3_500.0)),
+                        AngularVelocity::new(rng.gen_range(1.0e-6..=1.0e-5)),
+                    );
+                    let clearance = (scale.drawn_body_radius(body.radius) + scale.drawn_body_radius(moon_body.radius)) * 2.0;
+                    let innermost = (clearance / sphere_of_influence).max(0.05);
+                    if innermost >= 0.5 {
+                        continue;
+                    }
+
+                    let moon = generate_node(&planet.reference_frame, sphere_of_influence * rng.gen_range(innermost..=0.5), moon_body, scale, &mut rng);
+                    planet_children.push(HierarchyNode { node: moon,  children: None });
+                }
 
                 nodes.push(HierarchyNode { node: planet,  children: Some(planet_children) });
             }
@@ -61,13 +83,13 @@ fn transform_from_orbit(orbit: OrbitParameters, reference_frame: &Transform) ->
     }
 }
 
-fn generate_node(parent_reference: &Transform, rng: &mut Pcg64) -> OrbitNode {
+fn generate_node(parent_reference: &Transform, semimajor_axis: Length, body: OrbitalBody, scale: &SimulationScale, rng: &mut Pcg64) -> OrbitNode {
 
     // let mut rng = rand::thread_rng();
     
     let orbit = OrbitParameters {
         eccentricity: rng.gen_range(0.0..0.1),
-        semi_major_axis: rng.gen_range(1.0..=2.5),
+        semi_major_axis: scale.to_world(semimajor_axis) as f32,
         longitude_of_ascending_node: rng.gen_range(0.0..PI*2.),
         inclination: rng.gen_range(0.0..0.10),// PI*2.),
         argument_of_periapsis: rng.gen_range(0.0..0.10),// PI*2.),
@@ -77,7 +99,7 @@ fn generate_node(parent_reference: &Transform, rng: &mut Pcg64) -> OrbitNode {
     OrbitNode {
         orbit,
         reference_frame: transform_from_orbit(orbit, parent_reference),
-        body: OrbitalBody::from_sphere(0.1, 1.0, 0.2),
+        body,
     }
 }
 
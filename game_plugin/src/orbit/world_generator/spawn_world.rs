@@ -1,14 +1,15 @@
 use bevy::prelude::{Assets, BuildChildren, ChildBuilder, Commands, GlobalTransform, Mesh, Res, ResMut, StandardMaterial, Transform};
-use kepler::{EllipticalOrbit, OrbitalBody, OrbitalBodyBundle};
+use kepler::{Angle, EllipticalOrbit, Length, OrbitalBody, OrbitalBodyBundle};
 
-use crate::orbit::bundles::ReferenceFrameBundle;
+use crate::orbit::{SimulationScale, bundles::ReferenceFrameBundle};
 use super::{HierarchyNode, WorldGenerationSettings, generate_world::generate_world};
 
 pub(super) fn spawn_world(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    settings: Res<WorldGenerationSettings>
+    settings: Res<WorldGenerationSettings>,
+    scale: Res<SimulationScale>,
 ) {
     let sun_frame_transform = Transform::default();
     let sun_frame = commands
@@ -19,12 +20,12 @@ pub(super) fn spawn_world(
     // let sun_radius = 0.5;
     // let orbital_body_sun = OrbitalBody::from_sphere(0.5, 1.0, 0.1);
     let sun_transform = Transform::default();
+    let sun = OrbitalBody::sun();
     let sun_body = commands
         .spawn()
-        .insert_bundle(OrbitalBodyBundle::new(
-            0.5, 
-            1.5, 
-            1.0,
+        .insert_bundle(OrbitalBodyBundle::from_orbital_body(
+            sun,
+            scale.star_radius_to_world(sun.radius),
             sun_transform,
             &mut meshes))
         .id();
@@ -33,12 +34,12 @@ pub(super) fn spawn_world(
 
     let planet_orbit = EllipticalOrbit::new(
         0.9, 
-        4.5, 
-        0.0, 
-        0.0,
-        0.0,
-        0.0,
-        25.0);
+        Length::from_astronomical_units(4.5), 
+        Angle::new(0.0), 
+        Angle::new(0.0),
+        Angle::new(0.0),
+        Angle::new(0.0),
+        sun.gravitational_parameter());
 
     // let position = planet_orbit.get_position_vector(&sun_frame_transform);
     
@@ -120,24 +121,23 @@ pub(super) fn spawn_world(
 //         .id()
 //     ;
 // }
-fn spawn_node(node: HierarchyNode, builder: &mut ChildBuilder, mut meshes: &mut ResMut<Assets<Mesh>>) {
+fn spawn_node(node: HierarchyNode, builder: &mut ChildBuilder, mut meshes: &mut ResMut<Assets<Mesh>>, scale: &SimulationScale) {
     
     builder
         .spawn()
         .insert_bundle(ReferenceFrameBundle::from_transform(node.node.reference_frame))
         .insert(node.node.orbit)
         .with_children(|parent_builder | {
-            parent_builder.spawn_bundle(OrbitalBodyBundle::new(
-                node.node.body.radius,
-                node.node.body.density,
-                node.node.body.spin_velocity,
+            parent_builder.spawn_bundle(OrbitalBodyBundle::from_orbital_body(
+                node.node.body,
+                scale.body_radius_to_world(node.node.body.radius),
                 Transform::default(),
                  &mut meshes
             ));
 
             if let Some(child_nodes) = node.children {
                 for child_node in child_nodes {
-                    spawn_node(child_node, parent_builder, &mut meshes);
+                    spawn_node(child_node, parent_builder, &mut meshes, scale);
                 }
             }
         })
@@ -2,13 +2,30 @@ mod move_bodies;
 mod rotate_bodies;
 mod orbit_driver;
 mod debug_ellipse;
+mod execute_maneuvers;
+mod sphere_of_influence;
+mod trajectory_components;
+mod n_body;
+mod precession;
+mod atmospheric_drag;
+mod third_body;
+mod update_orbiters;
 
 use move_bodies::move_orbital_bodies;
 use rotate_bodies::rotate_orbital_bodies;
 use orbit_driver::drive_orbits;
+use n_body::{propagate_n_body, seed_n_body_states};
+use precession::precess_orbits;
+use sphere_of_influence::update_spheres_of_influence;
+use update_orbiters::update_orbiters;
 use debug_ellipse::{increase_periapsis_arg, increase_ascending_arg, increase_inclination_arg, update_orbital_plane_transform, incrase_eccentricity_arg};
 
 use bevy::prelude::{AppBuilder, IntoSystem, ParallelSystemDescriptorCoercion, Plugin};
+use kepler::{Ellipse, Hyperbola, Parabola};
+
+pub use n_body::{NBodyPropagation, PropagationMode};
+pub use atmospheric_drag::SurfaceImpact;
+pub(crate) use trajectory_components::orbit_trajectory;
 
 use self::debug_ellipse::{rotate_refs, update_orbital_body_transform};
 
@@ -17,6 +34,10 @@ pub struct WorldUpdaterPlugin;
 impl Plugin for WorldUpdaterPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app
+            .init_resource::<PropagationMode>()
+            .init_resource::<NBodyPropagation>()
+            .add_system(seed_n_body_states.system().label("seed"))
+            .add_system(propagate_n_body.system().after("seed"))
             // .add_system(drive_orbits.system().label("driver"))
             // .add_system(move_orbital_bodies.system().after("driver"))
             // .add_system(rotate_orbital_bodies.system().after("driver"))
@@ -27,8 +48,14 @@ impl Plugin for WorldUpdaterPlugin {
             // .add_system(incrase_eccentricity_arg.system().before("second"))
 
             // .add_system(rotate_refs.system())
+            .add_system(update_spheres_of_influence.system().label("soi"))
+            .add_event::<SurfaceImpact>()
+            .add_system(update_orbiters.system().label("orbiters").after("soi").before("second"))
+            .add_system(precess_orbits.system().before("second"))
             .add_system(update_orbital_plane_transform.system().label("second").before("third"))
-            .add_system(update_orbital_body_transform.system().label("third").after("second"))
+            .add_system(update_orbital_body_transform::<Ellipse>.system().label("third").after("second"))
+            .add_system(update_orbital_body_transform::<Hyperbola>.system().label("third").after("second"))
+            .add_system(update_orbital_body_transform::<Parabola>.system().label("third").after("second"))
         ;
     }
 }
\ No newline at end of file
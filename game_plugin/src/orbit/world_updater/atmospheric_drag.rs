@@ -0,0 +1,83 @@
+use bevy::{math::DVec3, prelude::Entity};
+use kepler::{Angle, DeltaV, EllipticalOrbit, OrbitalBody, Time, Trajectory};
+
+use crate::orbit::components::Drag;
+
+
+/// Sent when the orbit of a body decays into the surface of the body it orbits
+pub struct SurfaceImpact {
+    pub entity: Entity,
+    /// The entity of the reference frame of the body that was hit
+    pub parent: Entity,
+    pub time: kepler::Time,
+}
+
+/// What the atmosphere of the primary did to an orbiter over a frame
+pub(super) enum DragEffect {
+    /// The orbiter is on the returned trajectory from `now` on
+    Slowed(Trajectory),
+    /// The orbiter hit the surface during the frame
+    Impact,
+}
+
+/// Slows down an orbiter whose periapsis lies inside the atmosphere of `body`, the body it orbits
+///
+/// The drag of the frame ending at `now` is applied as a small impulse at the current position,
+/// starting the orbiter on a new orbit from that time. `None` if the orbiter feels no drag over the frame.
+pub(super) fn apply_atmospheric_drag(trajectory: &Trajectory, drag: &Drag, body: &OrbitalBody, now: Time, dt: Time) -> Option<DragEffect> {
+    let orbit = match trajectory {
+        Trajectory::Elliptical(orbit) => orbit,
+        _ => return None,
+    };
+    let top_altitude = body.atmosphere?.top_altitude;
+    if orbit.periapsis() - body.radius >= top_altitude {
+        return None;
+    }
+
+    if orbit.periapsis() <= body.radius && reaches_surface(orbit, body, now - dt, dt) {
+        return Some(DragEffect::Impact);
+    }
+
+    let (position, velocity) = orbit.state_vectors_at(now);
+
+    let delta_v = body.drag_acceleration(*position.val(), *velocity.val(), drag.ballistic_coefficient) * *dt.val();
+    // Above the atmosphere at the moment, the orbit stays as it is
+    if delta_v == DVec3::ZERO {
+        return None;
+    }
+
+    orbit.apply_impulse(now, DeltaV::Inertial(delta_v)).map(DragEffect::Slowed)
+}
+
+/// Whether an orbit whose periapsis lies below the surface brings the body down to it
+/// within `dt` of `start`, or already had it below the surface then
+///
+/// Checked along the orbit rather than at the end of the frame, so that a large time step
+/// cannot carry the body through the surface and out the other side.
+fn reaches_surface(orbit: &EllipticalOrbit, body: &OrbitalBody, start: Time, dt: Time) -> bool {
+    // The whole orbit lies below the surface, as a circular one does once its periapsis does
+    if orbit.apoapsis() <= body.radius {
+        return true;
+    }
+
+    // Where the orbit crosses the surface on the way down to the periapsis, `r = p / (1 + e cos θ)`
+    let semi_latus_rectum = orbit.semimajor_axis() * (1.0 - orbit.eccentricity().powf(2.0));
+    let surface_anomaly = -Angle::new(((semi_latus_rectum / body.radius - 1.0) / orbit.eccentricity()).clamp(-1.0, 1.0).acos());
+
+    let start_anomaly = orbit.true_anomaly_at_time(start).wrapped_signed();
+    if start_anomaly.val().abs() <= surface_anomaly.val().abs() {
+        return true;
+    }
+
+    orbit.time_of_flight(start_anomaly, surface_anomaly) <= dt
+}
+
+#[test]
+fn circular_orbit_below_the_surface_reaches_it() {
+    use kepler::{AngularVelocity, Density, GravitationalParameter, Length};
+
+    let earth = OrbitalBody::from_sphere(Length::new(6.371e6), Density::new(5_514.0), AngularVelocity::new(7.29e-5));
+    let orbit = EllipticalOrbit::new(0.0, Length::new(6.3e6), Angle::new(1.0), Angle::new(0.0), Angle::new(0.3), Angle::new(0.0), GravitationalParameter::from_value(3.986e14));
+
+    assert!(reaches_surface(&orbit, &earth, Time::new(100.0), Time::new(1.0)));
+}
@@ -1,48 +1,42 @@
-use std::{f32::consts::PI, ops::Rem};
+use bevy::{core::Time, ecs::component::Component, math::{Quat, Vec3}, prelude::{Changed, Children, GlobalTransform, Parent, Query, Res, Transform, With, Without}};
+use kepler::{Angle, Conic, Ellipse, EllipticalOrbit, HyperbolicOrbit, OrbitalBody, OrbitalPlane, ParabolicOrbit, get_orbital_position_relative};
 
-use bevy::{core::Time, math::{Quat, Vec3}, prelude::{Changed, Children, GlobalTransform, Parent, Query, Res, Transform, With, Without}};
-use kepler::{Ellipse, OrbitalBody, OrbitalPlane, get_orbital_position_relative};
+use crate::orbit::{SimulationScale, components::ReferenceFrame};
 
-use crate::orbit::components::ReferenceFrame;
+use super::{PropagationMode, trajectory_components::orbit_trajectory};
 
 
 pub fn increase_periapsis_arg(mut q: Query<&mut OrbitalPlane>, time: Res<Time>) {
     for mut plane in q.iter_mut() {
         let rate = 0.5;
-        let new_angle = rate * time.delta_seconds() as f32 + plane.periapsis_arg();
-        let clamped_angle = new_angle.rem(2.0*PI);
+        let new_angle = Angle::new(rate * time.delta_seconds_f64()) + plane.periapsis_arg();
 
-        // println!("new_angle: {:?} : {:?}", new_angle, clamped_angle);
-        plane.set_periapsis_arg(clamped_angle);
+        plane.set_periapsis_arg(new_angle.wrapped());
     }
 }
 
 pub fn increase_ascending_arg(mut q: Query<&mut OrbitalPlane>, time: Res<Time>) {
     for mut plane in q.iter_mut() {
         let rate = 0.5;
-        let new_angle = rate * time.delta_seconds() as f32 + plane.ascending_arg();
-        let clamped_angle = new_angle.rem(2.0*PI);
+        let new_angle = Angle::new(rate * time.delta_seconds_f64()) + plane.ascending_arg();
 
-        // println!("new_angle: {:?} : {:?}", new_angle, clamped_angle);
-        plane.set_ascending_arg(clamped_angle);
+        plane.set_ascending_arg(new_angle.wrapped());
     }
 }
 
 pub fn increase_inclination_arg(mut q: Query<&mut OrbitalPlane>, time: Res<Time>) {
     for mut plane in q.iter_mut() {
         let rate = 0.5;
-        let new_angle = rate * time.delta_seconds() as f32 + plane.inclination_arg();
-        let clamped_angle = new_angle.rem(2.0*PI);
+        let new_angle = Angle::new(rate * time.delta_seconds_f64()) + plane.inclination_arg();
 
-        // println!("new_angle: {:?} : {:?}", new_angle, clamped_angle);
-        plane.set_inclination_arg(clamped_angle);
+        plane.set_inclination_arg(new_angle.wrapped());
     }
 }
 
 pub fn incrase_eccentricity_arg(mut q: Query<&mut Ellipse>, time: Res<Time>) {
     for mut ellipse in q.iter_mut() {
         println!("Ellipse");
-        let new_eccentricity = (1.0 + (time.seconds_since_startup() as f32).sin()) / 2.0; // / 2.0 + 1.0;
+        let new_eccentricity = (1.0 + time.seconds_since_startup().sin()) / 2.0; // / 2.0 + 1.0;
         println!("New Eccentricity {}", new_eccentricity);
         ellipse.set_eccentricity(new_eccentricity);
 
@@ -59,16 +53,27 @@ pub fn update_orbital_plane_transform(
     }
 }
 
-/// 
-pub fn update_orbital_body_transform(
+/// Places each orbital body on its parent's conic at the true anomaly its orbit has reached
+///
+/// Left to `propagate_n_body` when numerical propagation is opted into.
+pub fn update_orbital_body_transform<C: Conic + Component>(
     mut q: Query<(&mut Transform, &Parent), With<OrbitalBody>>,
-    p_q: Query<(&OrbitalPlane, &Ellipse), With<Children>>,
+    p_q: Query<(&OrbitalPlane, &C, Option<&EllipticalOrbit>, Option<&ParabolicOrbit>, Option<&HyperbolicOrbit>), With<Children>>,
+    mode: Res<PropagationMode>,
     time: Res<Time>,
+    scale: Res<SimulationScale>,
 ) {
+    if let PropagationMode::NBody(_) = *mode {
+        return;
+    }
+
+    let now = scale.simulated_time(time.seconds_since_startup());
+
     for (mut transform, parent) in q.iter_mut() {
-        if let Ok((p_plane, p_ellipse)) = p_q.get(parent.0) {
-            let angle = get_cyclic_time(0.2, &time);
-            transform.translation = get_orbital_position_relative(p_plane, p_ellipse, angle);
+        if let Ok((p_plane, p_conic, elliptical, parabolic, hyperbolic)) = p_q.get(parent.0) {
+            if let Some(trajectory) = orbit_trajectory(elliptical, parabolic, hyperbolic) {
+                transform.translation = get_orbital_position_relative(p_plane, p_conic, *trajectory.true_anomaly_at_time(now).val());
+            }
         }
     }
 }
@@ -77,11 +82,7 @@ pub fn update_orbital_body_transform(
 
 pub fn rotate_refs(mut q: Query<&mut Transform, With<ReferenceFrame>>, time: Res<Time>) {
     for mut transform in q.iter_mut() {
-        transform.rotation = Quat::from_axis_angle(Vec3::X, (0.2 * time.seconds_since_startup() as f32).rem(2.0 * PI));
+        transform.rotation = Quat::from_axis_angle(Vec3::X, *Angle::new(0.2 * time.seconds_since_startup()).wrapped().val() as f32);
     }
 }
 
-pub fn get_cyclic_time(speed: f32, time: &Res<Time>) -> f32 {
-    (speed * time.seconds_since_startup() as f32).rem(2.0 * PI)
-}
-
@@ -0,0 +1,15 @@
+use kepler::{Time, Trajectory};
+
+use crate::orbit::components::ManeuverNode;
+
+
+/// The trajectory after the burn of the maneuver node, once the simulation time has reached it
+///
+/// `None` before then, or if the burn leaves no orbit to follow.
+pub(super) fn execute_maneuver(trajectory: &Trajectory, node: &ManeuverNode, now: Time) -> Option<Trajectory> {
+    if now < node.time {
+        return None;
+    }
+
+    trajectory.apply_impulse(node.time, node.delta_v)
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use bevy::{core::Time, math::{DQuat, DVec3, Quat}, prelude::{Commands, Entity, Parent, Query, Res, ResMut, Transform, With, Without}};
+use kepler::{EllipticalOrbit, HyperbolicOrbit, Integrator, NBodySimulation, OrbitalBody, OrbitalPositionVector, OrbitalVelocityVector, ParabolicOrbit, PointMass, StepStatistics};
+
+use crate::orbit::{SimulationScale, components::NBodyState};
+use super::trajectory_components::orbit_trajectory;
+
+
+/// Longest step of simulated time the numerical propagation takes, in seconds
+///
+/// A frame spanning more, as it does at the default time scale, is split into equal substeps.
+const MAX_STEP: f64 = 600.0;
+
+/// How the simulation moves its bodies, read every frame
+///
+/// Bodies follow their orbits unless the app asks for `NBody`,
+/// either as a resource inserted up front or by switching to it while running.
+#[derive(Default)]
+pub enum PropagationMode {
+    /// Every body follows its two-body orbit around its parent
+    #[default]
+    Analytic,
+
+    /// Every orbital body attracts every other, integrated with the given scheme
+    ///
+    /// Bodies start from where their orbits have them when the mode is opted into.
+    NBody(Integrator),
+}
+
+/// The numerical propagation carried over from frame to frame
+#[derive(Default)]
+pub struct NBodyPropagation {
+    simulation: Option<NBodySimulation>,
+
+    /// Entity of each body of the simulation, in the same order
+    entities: Vec<Entity>,
+
+    /// Work of the adaptive integrator in the simulations given up so far
+    earlier_statistics: StepStatistics,
+}
+
+impl NBodyPropagation {
+    /// Work done by the adaptive integrator since numerical propagation was opted into
+    pub fn statistics(&self) -> StepStatistics {
+        match &self.simulation {
+            Some(simulation) => self.earlier_statistics.merge(&simulation.statistics()),
+            None => self.earlier_statistics,
+        }
+    }
+
+    /// Gives up the current simulation, keeping its statistics
+    fn reset(&mut self) {
+        if let Some(simulation) = self.simulation.take() {
+            self.earlier_statistics = self.earlier_statistics.merge(&simulation.statistics());
+        }
+        self.entities.clear();
+    }
+}
+
+/// Frames the numerical propagation does not move, with the orbits that place bodies within them
+type Frames<'w, 'a> = Query<'w, (&'a Transform, Option<&'a Parent>, Option<&'a OrbitalBody>, Option<&'a EllipticalOrbit>, Option<&'a ParabolicOrbit>, Option<&'a HyperbolicOrbit>), Without<NBodyState>>;
+
+/// A numerically propagated body, with what places it in the hierarchy
+struct PropagatedBody {
+    position: DVec3,
+    velocity: DVec3,
+    rotation: Quat,
+    parent: Option<Entity>,
+}
+
+impl PropagatedBody {
+    fn new(state: &NBodyState, transform: &Transform, parent: Option<&Parent>) -> Self {
+        PropagatedBody {
+            position: state.position,
+            velocity: state.velocity,
+            rotation: transform.rotation,
+            parent: parent.map(|parent| parent.0),
+        }
+    }
+}
+
+/// Gives every orbital body an `NBodyState` from where its orbit has it, when numerical propagation is opted into
+pub(crate) fn seed_n_body_states(
+    mut commands: Commands,
+    mode: Res<PropagationMode>,
+    unseeded: Query<Entity, (With<OrbitalBody>, Without<NBodyState>)>,
+    seeded: Query<(Entity, &NBodyState, &Transform, Option<&Parent>)>,
+    frames: Frames,
+    time: Res<Time>,
+    scale: Res<SimulationScale>,
+) {
+    if let PropagationMode::Analytic = *mode {
+        return;
+    }
+
+    let now = scale.simulated_time(time.seconds_since_startup());
+    let propagated = seeded
+        .iter()
+        .map(|(entity, state, transform, parent)| (entity, PropagatedBody::new(state, transform, parent)))
+        .collect();
+
+    for entity in unseeded.iter() {
+        let (position, velocity, _) = inertial_state(Some(entity), now, &propagated, &frames, &scale);
+        commands.entity(entity).insert(NBodyState { position, velocity });
+    }
+}
+
+/// Integrates the mutual attraction of all bodies with an `NBodyState`, when opted into
+///
+/// The states share one inertial frame, each body's transform is placed back within its parent's frame.
+/// The simulation is kept from one frame to the next, and built again from the states whenever a body joins it.
+pub(crate) fn propagate_n_body(
+    mode: Res<PropagationMode>,
+    mut propagation: ResMut<NBodyPropagation>,
+    mut query: Query<(Entity, &OrbitalBody, &mut NBodyState, &mut Transform, Option<&Parent>)>,
+    frames: Frames,
+    time: Res<Time>,
+    scale: Res<SimulationScale>,
+) {
+    let integrator = match *mode {
+        PropagationMode::NBody(integrator) => integrator,
+        PropagationMode::Analytic => return,
+    };
+
+    let entities: Vec<Entity> = query.iter_mut().map(|(entity, ..)| entity).collect();
+    if propagation.entities != entities {
+        propagation.reset();
+        let bodies = query
+            .iter_mut()
+            .map(|(_, body, state, _, _)| {
+                PointMass::from_body(body, OrbitalPositionVector::new(state.position), OrbitalVelocityVector::new(state.velocity))
+            })
+            .collect();
+        propagation.simulation = Some(NBodySimulation::new(bodies, integrator));
+        propagation.entities = entities;
+    }
+
+    let simulation = match propagation.simulation.as_mut() {
+        Some(simulation) => simulation,
+        None => return,
+    };
+    simulation.set_integrator(integrator);
+
+    // A failed step leaves every body where it was, rather than writing out non-finite states,
+    // and the simulation is built again from there on the next frame
+    if !simulation.propagate(*scale.simulated_time(time.delta_seconds_f64()).val(), MAX_STEP) {
+        propagation.reset();
+        return;
+    }
+
+    for ((_, _, mut state, _, _), point) in query.iter_mut().zip(simulation.bodies()) {
+        state.position = point.position;
+        state.velocity = point.velocity;
+    }
+
+    let now = scale.simulated_time(time.seconds_since_startup());
+    let propagated = query
+        .iter_mut()
+        .map(|(entity, _, state, transform, parent)| (entity, PropagatedBody::new(&state, &transform, parent)))
+        .collect();
+
+    for (_, _, state, mut transform, parent) in query.iter_mut() {
+        let (parent_position, _, parent_rotation) = inertial_state(parent.map(|parent| parent.0), now, &propagated, &frames, &scale);
+        transform.translation = scale.position_to_world(parent_rotation.inverse() * (state.position - parent_position));
+    }
+}
+
+/// Position, velocity and orientation of the frame of an entity in the inertial frame of the simulation
+///
+/// Propagated bodies are where their state puts them, other orbital bodies where the orbit of their parent
+/// puts them, as `update_orbital_body_transform` does, and any other frame where its transform puts it.
+/// No entity is the root of the hierarchy, at rest at the origin.
+fn inertial_state(
+    entity: Option<Entity>,
+    now: kepler::Time,
+    propagated: &HashMap<Entity, PropagatedBody>,
+    frames: &Frames,
+    scale: &SimulationScale,
+) -> (DVec3, DVec3, DQuat) {
+    let entity = match entity {
+        Some(entity) => entity,
+        None => return (DVec3::ZERO, DVec3::ZERO, DQuat::IDENTITY),
+    };
+
+    if let Some(body) = propagated.get(&entity) {
+        let (_, _, parent_rotation) = inertial_state(body.parent, now, propagated, frames, scale);
+        return (body.position, body.velocity, parent_rotation * body.rotation.as_f64());
+    }
+
+    let (transform, parent, body, ..) = match frames.get(entity) {
+        Ok(frame) => frame,
+        Err(_) => return (DVec3::ZERO, DVec3::ZERO, DQuat::IDENTITY),
+    };
+    let (parent_position, parent_velocity, parent_rotation) = inertial_state(parent.map(|parent| parent.0), now, propagated, frames, scale);
+
+    // Offset from the origin of the parent, in the parent's orientation
+    let parent_orbit = parent
+        .filter(|_| body.is_some())
+        .and_then(|parent| frames.get(parent.0).ok())
+        .and_then(|(parent_transform, _, _, elliptical, parabolic, hyperbolic)| {
+            orbit_trajectory(elliptical, parabolic, hyperbolic).map(|trajectory| (parent_transform, trajectory))
+        });
+    let (offset, velocity) = match parent_orbit {
+        Some((parent_transform, trajectory)) => {
+            // The state vectors are oriented like the parent of the orbit, without the rotation of its plane
+            let (position, velocity) = trajectory.state_vectors_at(now);
+            let plane = parent_transform.rotation.as_f64().inverse();
+            (plane * *position.val(), plane * *velocity.val())
+        }
+        None => (transform.translation.as_f64() * *scale.length.val(), DVec3::ZERO),
+    };
+
+    (parent_position + parent_rotation * offset, parent_velocity + parent_rotation * velocity, parent_rotation * transform.rotation.as_f64())
+}
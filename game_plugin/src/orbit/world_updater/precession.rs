@@ -0,0 +1,33 @@
+use bevy::{core::Time, prelude::{Children, Parent, Query, Res}};
+use kepler::{EllipticalOrbit, OrbitalBody, OrbitalPlane, SecularRates};
+
+use crate::orbit::SimulationScale;
+use super::sphere_of_influence::find_body;
+
+
+/// Keeps the secular rates of each orbit in line with the oblateness of the body it orbits,
+/// and turns its orbital plane along with them
+pub(crate) fn precess_orbits(
+    mut orbits: Query<(&mut EllipticalOrbit, &mut OrbitalPlane, &Parent)>,
+    children: Query<&Children>,
+    bodies: Query<&OrbitalBody>,
+    time: Res<Time>,
+    scale: Res<SimulationScale>,
+) {
+    for (mut orbit, mut plane, parent) in orbits.iter_mut() {
+        let rates = match children.get(parent.0).ok().and_then(|children| find_body(children, &bodies)) {
+            Some(body) => body.oblateness_rates(&orbit),
+            None => SecularRates::default(),
+        };
+
+        if orbit.secular_rates() != rates {
+            orbit.set_secular_rates(rates);
+        }
+
+        if rates != SecularRates::default() {
+            let time = scale.simulated_time(time.seconds_since_startup());
+            plane.set_ascending_arg(orbit.longitude_of_ascending_node_at(time));
+            plane.set_periapsis_arg(orbit.argument_of_periapsis_at(time));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::{math::DQuat, prelude::{Changed, Children, Commands, Entity, Parent, Query}};
+use kepler::{EllipticalOrbit, OrbitalBody, OrbitalPositionVector, OrbitalVelocityVector, Time, Trajectory};
+
+use crate::orbit::components::SphereOfInfluence;
+use super::update_orbiters::PrimaryFrames;
+
+
+/// Recomputes the sphere of influence of every reference frame on an elliptical orbit,
+/// from the masses of its own body and of the body of its parent
+pub(crate) fn update_spheres_of_influence(
+    mut commands: Commands,
+    frames: Query<(Entity, &EllipticalOrbit, &Parent, &Children), Changed<EllipticalOrbit>>,
+    children: Query<&Children>,
+    bodies: Query<&OrbitalBody>,
+) {
+    for (entity, orbit, parent, own_children) in frames.iter() {
+        let parent_children = match children.get(parent.0) {
+            Ok(parent_children) => parent_children,
+            Err(_) => continue,
+        };
+
+        if let (Some(body), Some(parent_body)) = (find_body(own_children, &bodies), find_body(parent_children, &bodies)) {
+            let radius = body.sphere_of_influence(&parent_body, orbit.semimajor_axis());
+            commands.entity(entity).insert(SphereOfInfluence { radius: *radius.val() });
+        }
+    }
+}
+
+/// The primary an orbiter passes to when it left the sphere of influence of its primary for the grandparent,
+/// or entered the sphere of influence of a sibling, with its trajectory at `time` relative to that primary
+pub(super) fn patch_conic_transition(
+    entity: Entity,
+    trajectory: &Trajectory,
+    primary: Entity,
+    frames: &PrimaryFrames,
+    bodies: &Query<&OrbitalBody>,
+    time: Time,
+) -> Option<(Entity, Trajectory)> {
+    let position = *trajectory.state_vectors_at(time).0.val();
+    let (grandparent, primary_orbit, primary_sphere, siblings, primary_transform) = frames.get(primary).ok()?;
+
+    // The new primary, the rotation from the axes of the current primary into its axes,
+    // and the state of the current primary relative to it
+    let mut transition: Option<(Entity, DQuat, OrbitalPositionVector, OrbitalVelocityVector)> = None;
+
+    // Leaving: the primary is where its own orbit puts it around the grandparent, in the axes of the grandparent
+    if let (Some(grandparent), Some(primary_orbit), Some(sphere)) = (grandparent, primary_orbit, primary_sphere) {
+        if position.length() > sphere.radius {
+            let (primary_position, primary_velocity) = primary_orbit.state_vectors_at(time);
+            transition = Some((grandparent.0, primary_transform.rotation.as_f64(), primary_position, primary_velocity));
+        }
+    }
+
+    // Entering: the primary is opposite to where the sibling's orbit puts it, turned into the axes of the sibling.
+    // The orbits of the orbiter and of its siblings share the axes of the primary, so they compare as they are
+    if transition.is_none() {
+        for &sibling in siblings.iter().filter(|&&sibling| sibling != entity) {
+            if let Ok((_, Some(sibling_orbit), Some(sphere), _, sibling_transform)) = frames.get(sibling) {
+                let (sibling_position, sibling_velocity) = sibling_orbit.state_vectors_at(time);
+
+                if (position - *sibling_position.val()).length() < sphere.radius {
+                    let rotation = sibling_transform.rotation.as_f64().inverse();
+                    transition = Some((
+                        sibling,
+                        rotation,
+                        OrbitalPositionVector::new(rotation * -*sibling_position.val()),
+                        OrbitalVelocityVector::new(rotation * -*sibling_velocity.val()),
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+
+    let (new_primary, rotation, primary_position, primary_velocity) = transition?;
+    let primary_body = frames.get(new_primary).ok().and_then(|(_, _, _, children, _)| find_body(children, bodies))?;
+
+    let trajectory = trajectory.relative_to(time, rotation, primary_position, primary_velocity, primary_body.gravitational_parameter())?;
+
+    Some((new_primary, trajectory))
+}
+
+/// The body among the children of a reference frame, which gives the frame its mass
+pub(crate) fn find_body(children: &Children, bodies: &Query<&OrbitalBody>) -> Option<OrbitalBody> {
+    children.iter().find_map(|&child| bodies.get(child).ok().copied())
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::{Entity, Query};
+use kepler::{DormandPrince, OrbitalBody, ThirdBody, Time, Trajectory, propagate_perturbed};
+
+use super::{sphere_of_influence::find_body, update_orbiters::PrimaryFrames};
+
+
+/// The body the primary of an orbiter orbits and the siblings of the orbiter, whose tides perturb it
+pub(super) fn third_bodies(entity: Entity, primary: Entity, frames: &PrimaryFrames, bodies: &Query<&OrbitalBody>) -> Vec<ThirdBody> {
+    let (grandparent, primary_orbit, _, siblings, primary_transform) = match frames.get(primary) {
+        Ok(frame) => frame,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut third_bodies = Vec::new();
+
+    if let (Some(grandparent), Some(primary_orbit)) = (grandparent, primary_orbit) {
+        if let Some(body) = frames.get(grandparent.0).ok().and_then(|(_, _, _, children, _)| find_body(children, bodies)) {
+            // The orbit of the primary is in the axes of the grandparent, which the primary is turned against
+            let rotation = primary_transform.rotation.as_f64().inverse();
+            third_bodies.push(ThirdBody::grandparent(body.gravitational_parameter(), *primary_orbit, rotation));
+        }
+    }
+
+    // The orbits of siblings are in the axes of the primary already
+    for &sibling in siblings.iter().filter(|&&sibling| sibling != entity) {
+        if let Ok((_, Some(sibling_orbit), _, children, _)) = frames.get(sibling) {
+            if let Some(body) = find_body(children, bodies) {
+                third_bodies.push(ThirdBody::sibling(body.gravitational_parameter(), *sibling_orbit));
+            }
+        }
+    }
+
+    third_bodies
+}
+
+/// Moves an orbiter under the tides of `third_bodies` from the epoch of its trajectory up to `now`,
+/// returning the osculating trajectory then, with that time as its epoch
+///
+/// `None` if the integration fails or ends in radial motion, leaving the orbiter on its unperturbed trajectory.
+pub(super) fn apply_third_body_perturbations(
+    trajectory: &Trajectory,
+    third_bodies: &[ThirdBody],
+    integrator: &DormandPrince,
+    now: Time,
+) -> Option<Trajectory> {
+    // The epoch is where the previous frame left the orbiter
+    let epoch = trajectory.epoch();
+    let (position, velocity) = trajectory.state_vectors_at(epoch);
+    let mu = trajectory.gravitational_parameter();
+    let (position, velocity, _) = propagate_perturbed(integrator, position, velocity, mu, third_bodies, epoch, now - epoch)?;
+
+    let mut trajectory = Trajectory::from_state_vectors(position, velocity, mu)?;
+    trajectory.set_epoch(now);
+
+    Some(trajectory)
+}
@@ -0,0 +1,58 @@
+use bevy::ecs::system::EntityCommands;
+use kepler::{Ellipse, EllipticalOrbit, Hyperbola, HyperbolicOrbit, OrbitalPlane, Parabola, ParabolicOrbit, Trajectory};
+
+use crate::orbit::SimulationScale;
+
+
+/// Swaps whichever orbit and shape components the entity has for those of the given trajectory,
+/// with the shape sized in world units
+pub(crate) fn replace_orbit_components(entity_commands: &mut EntityCommands, trajectory: Trajectory, scale: &SimulationScale) {
+    remove_orbit_components(entity_commands);
+
+    match trajectory {
+        Trajectory::Elliptical(orbit) => {
+            entity_commands
+                .insert(Ellipse::from_major(scale.to_world(orbit.semimajor_axis()), orbit.eccentricity()))
+                .insert(OrbitalPlane::new(orbit.longitude_of_ascending_node(), orbit.inclination(), orbit.argument_of_periapsis()))
+                .insert(orbit);
+        }
+        Trajectory::Parabolic(orbit) => {
+            entity_commands
+                .insert(Parabola::new(scale.to_world(orbit.periapsis())))
+                .insert(OrbitalPlane::new(orbit.longitude_of_ascending_node(), orbit.inclination(), orbit.argument_of_periapsis()))
+                .insert(orbit);
+        }
+        Trajectory::Hyperbolic(orbit) => {
+            entity_commands
+                .insert(Hyperbola::from_major(scale.to_world(-orbit.semimajor_axis()), orbit.eccentricity()))
+                .insert(OrbitalPlane::new(orbit.longitude_of_ascending_node(), orbit.inclination(), orbit.argument_of_periapsis()))
+                .insert(orbit);
+        }
+    }
+}
+
+/// Takes whichever orbit, shape and plane components the entity has off it
+pub(crate) fn remove_orbit_components(entity_commands: &mut EntityCommands) {
+    entity_commands
+        .remove::<EllipticalOrbit>()
+        .remove::<ParabolicOrbit>()
+        .remove::<HyperbolicOrbit>()
+        .remove::<Ellipse>()
+        .remove::<Parabola>()
+        .remove::<Hyperbola>()
+        .remove::<OrbitalPlane>();
+}
+
+/// The trajectory of whichever orbit component is present
+pub(crate) fn orbit_trajectory(
+    elliptical: Option<&EllipticalOrbit>,
+    parabolic: Option<&ParabolicOrbit>,
+    hyperbolic: Option<&HyperbolicOrbit>,
+) -> Option<Trajectory> {
+    match (elliptical, parabolic, hyperbolic) {
+        (Some(orbit), _, _) => Some(Trajectory::Elliptical(*orbit)),
+        (_, Some(orbit), _) => Some(Trajectory::Parabolic(*orbit)),
+        (_, _, Some(orbit)) => Some(Trajectory::Hyperbolic(*orbit)),
+        _ => None,
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::{core::Time, prelude::{Children, Commands, Entity, EventWriter, Parent, Query, Res, Transform, With}};
+use kepler::{DormandPrince, EllipticalOrbit, HyperbolicOrbit, OrbitalBody, ParabolicOrbit, ThirdBody, Trajectory};
+
+use crate::orbit::{SimulationScale, components::{Drag, ManeuverNode, ReferenceFrame, SphereOfInfluence, ThirdBodyPerturbations}};
+use super::{
+    atmospheric_drag::{DragEffect, SurfaceImpact, apply_atmospheric_drag},
+    execute_maneuvers::execute_maneuver,
+    sphere_of_influence::{find_body, patch_conic_transition},
+    third_body::{apply_third_body_perturbations, third_bodies},
+    trajectory_components::{orbit_trajectory, remove_orbit_components, replace_orbit_components},
+};
+
+
+/// Reference frames that can be the primary of an orbiter, with what places their bodies around their own parent
+///
+/// The orbits of the children of a frame are given in its axes, which its transform turns into those of its parent.
+pub(super) type PrimaryFrames<'w, 'a> = Query<
+    'w,
+    (Option<&'a Parent>, Option<&'a EllipticalOrbit>, Option<&'a SphereOfInfluence>, &'a Children, &'a Transform),
+    With<ReferenceFrame>,
+>;
+
+/// Entities on an orbit, with whatever can change it over a frame
+type Orbiters<'w, 'a> = Query<'w, (
+    Entity,
+    &'a Parent,
+    Option<&'a EllipticalOrbit>,
+    Option<&'a ParabolicOrbit>,
+    Option<&'a HyperbolicOrbit>,
+    Option<&'a ManeuverNode>,
+    Option<&'a Drag>,
+    Option<&'a ThirdBodyPerturbations>,
+)>;
+
+/// What one frame did to the trajectory of an orbiter
+enum OrbiterUpdate {
+    Unchanged,
+    Replaced(Trajectory),
+    /// The orbiter hit the surface of its primary
+    Impact,
+}
+
+/// Carries an orbiter through the burn of its maneuver node, the tides of third bodies
+/// and the drag of the atmosphere of its primary over the frame ending at `now`,
+/// each starting from the trajectory the previous one left it on
+fn advance_trajectory(
+    trajectory: Trajectory,
+    maneuver: Option<&ManeuverNode>,
+    tides: Option<(&DormandPrince, &[ThirdBody])>,
+    drag: Option<(&Drag, &OrbitalBody)>,
+    now: kepler::Time,
+    dt: kepler::Time,
+) -> OrbiterUpdate {
+    let mut updated = maneuver.and_then(|node| execute_maneuver(&trajectory, node, now));
+
+    if let Some((integrator, third_bodies)) = tides.filter(|(_, third_bodies)| !third_bodies.is_empty()) {
+        if let Some(perturbed) = apply_third_body_perturbations(&updated.unwrap_or(trajectory), third_bodies, integrator, now) {
+            updated = Some(perturbed);
+        }
+    }
+
+    if let Some((drag, body)) = drag {
+        match apply_atmospheric_drag(&updated.unwrap_or(trajectory), drag, body, now, dt) {
+            Some(DragEffect::Slowed(slowed)) => updated = Some(slowed),
+            Some(DragEffect::Impact) => return OrbiterUpdate::Impact,
+            None => {}
+        }
+    }
+
+    match updated {
+        Some(trajectory) => OrbiterUpdate::Replaced(trajectory),
+        None => OrbiterUpdate::Unchanged,
+    }
+}
+
+/// Moves every orbiter through what changes its trajectory over the frame, then hands reference frames
+/// over to the primary whose sphere of influence they are in
+///
+/// The orbit components of each orbiter are replaced once, with the trajectory all of these left it on,
+/// so that none of them overwrites what another did in the same frame.
+pub(crate) fn update_orbiters(
+    mut commands: Commands,
+    orbiters: Orbiters,
+    frames: PrimaryFrames,
+    bodies: Query<&OrbitalBody>,
+    time: Res<Time>,
+    scale: Res<SimulationScale>,
+    mut impacts: EventWriter<SurfaceImpact>,
+) {
+    let now = scale.simulated_time(time.seconds_since_startup());
+    let dt = scale.simulated_time(time.delta_seconds_f64());
+
+    for (entity, parent, elliptical, parabolic, hyperbolic, node, drag, perturbed) in orbiters.iter() {
+        let trajectory = match orbit_trajectory(elliptical, parabolic, hyperbolic) {
+            Some(trajectory) => trajectory,
+            None => continue,
+        };
+        let primary_body = frames.get(parent.0).ok().and_then(|(_, _, _, children, _)| find_body(children, &bodies));
+        let third_bodies = match perturbed {
+            Some(_) => third_bodies(entity, parent.0, &frames, &bodies),
+            None => Vec::new(),
+        };
+        let tides = perturbed.map(|perturbations| (&perturbations.integrator, third_bodies.as_slice()));
+
+        let mut entity_commands = commands.entity(entity);
+        if node.is_some_and(|node| now >= node.time) {
+            entity_commands.remove::<ManeuverNode>();
+        }
+
+        let updated = match advance_trajectory(trajectory, node, tides, drag.zip(primary_body.as_ref()), now, dt) {
+            OrbiterUpdate::Unchanged => None,
+            OrbiterUpdate::Replaced(trajectory) => Some(trajectory),
+            OrbiterUpdate::Impact => {
+                impacts.send(SurfaceImpact {
+                    entity,
+                    parent: parent.0,
+                    time: now,
+                });
+                entity_commands.remove::<Drag>();
+                remove_orbit_components(&mut entity_commands);
+                continue;
+            }
+        };
+
+        // Only reference frames carry a sphere of influence along with them
+        let transition = frames
+            .get(entity)
+            .ok()
+            .and_then(|_| patch_conic_transition(entity, &updated.unwrap_or(trajectory), parent.0, &frames, &bodies, now));
+        let updated = match transition {
+            Some((new_primary, trajectory)) => {
+                entity_commands.insert(Parent(new_primary));
+                Some(trajectory)
+            }
+            None => updated,
+        };
+
+        if let Some(trajectory) = updated {
+            replace_orbit_components(&mut entity_commands, trajectory, &scale);
+        }
+    }
+}
+
+#[test]
+fn burn_and_drag_in_the_same_frame_both_count() {
+    use kepler::{Angle, AngularVelocity, Atmosphere, DeltaV, Density, GravitationalParameter, Length};
+
+    let mut earth = OrbitalBody::from_sphere(Length::new(6.371e6), Density::new(5_514.0), AngularVelocity::new(7.29e-5));
+    earth.atmosphere = Some(Atmosphere::new(Density::new(1.225), Length::new(8.5e3), Length::new(1.0e5)));
+    let mu = GravitationalParameter::from_value(3.986e14);
+
+    // Skimming the atmosphere 70 km up at the periapsis, with a prograde burn due there
+    let trajectory = Trajectory::Elliptical(EllipticalOrbit::new(0.08, Length::new(7.0e6), Angle::new(0.0), Angle::new(0.2), Angle::new(0.5), Angle::new(0.3), mu));
+    let node = ManeuverNode {
+        time: kepler::Time::new(0.0),
+        delta_v: DeltaV::Local { prograde: 10.0, normal: 0.0, radial: 0.0 },
+    };
+    let drag = Drag { ballistic_coefficient: 100.0 };
+    let (now, dt) = (kepler::Time::new(1.0), kepler::Time::new(1.0));
+
+    let updated = match advance_trajectory(trajectory, Some(&node), None, Some((&drag, &earth)), now, dt) {
+        OrbiterUpdate::Replaced(updated) => updated,
+        _ => panic!("the orbiter should be on a new trajectory"),
+    };
+
+    // The drag of the frame slows down the orbiter on the trajectory the burn put it on
+    let (position, velocity) = trajectory.apply_impulse(node.time, node.delta_v).unwrap().state_vectors_at(now);
+    let drag_delta_v = earth.drag_acceleration(*position.val(), *velocity.val(), drag.ballistic_coefficient) * *dt.val();
+    let (_, updated_velocity) = updated.state_vectors_at(now);
+
+    assert!(drag_delta_v.length() > 1.0e-2, "{}", drag_delta_v.length());
+    assert!((*updated_velocity.val() - (*velocity.val() + drag_delta_v)).length() < 1.0e-3, "{}", updated_velocity.val().length());
+}